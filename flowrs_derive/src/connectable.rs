@@ -42,6 +42,35 @@ pub fn impl_connectable_trait(ast: DeriveInput) -> TokenStream {
             arm_ast
         })
         .collect::<Vec<Arm>>();
+    let dyn_output_arms: Vec<Arm> = outputs
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let ident = &field.ident;
+            let arm: TokenStream = quote::quote! {
+                #index => Rc::new(self.#ident.clone()),
+            }
+            .into();
+            let arm_ast: Arm = syn::parse(arm.clone()).unwrap();
+            arm_ast
+        })
+        .collect::<Vec<Arm>>();
+    let input_names: Vec<String> = inputs
+        .iter()
+        .map(|field| field.ident.as_ref().unwrap().to_string())
+        .collect();
+    let output_names: Vec<String> = outputs
+        .iter()
+        .map(|field| field.ident.as_ref().unwrap().to_string())
+        .collect();
+    let input_indices = 0..input_len;
+    let output_indices = 0..output_len;
+    let optional_outputs: Vec<usize> = outputs
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| is_optional(field))
+        .map(|(index, _)| index)
+        .collect();
     let (_, ty_generics, _) = ast.generics.split_for_impl();
     let mut generic_bounds = get_generic_bounds(inputs.clone());
     generic_bounds.append(&mut get_generic_bounds(outputs));
@@ -63,6 +92,50 @@ pub fn impl_connectable_trait(ast: DeriveInput) -> TokenStream {
                     _ => panic!("Index {} out of bounds for {} with output len {}.", index, #struct_ident_str, #output_len),
                 }
             }
+
+            fn dyn_input_at(&self, index: usize) -> Rc<dyn flowrs::connection::AnyInput> {
+                match index {
+                    #(#inpu_arms)*
+                    _ => panic!("Index {} out of bounds for {} with input len {}.", index, #struct_ident_str, #input_len),
+                }
+            }
+
+            fn dyn_output_at(&self, index: usize) -> Rc<dyn flowrs::connection::AnyOutput> {
+                match index {
+                    #(#dyn_output_arms)*
+                    _ => panic!("Index {} out of bounds for {} with output len {}.", index, #struct_ident_str, #output_len),
+                }
+            }
+
+            fn output_is_optional(&self, index: usize) -> bool {
+                match index {
+                    #(#optional_outputs => true,)*
+                    i if i < #output_len => false,
+                    _ => panic!("Index {} out of bounds for {} with output len {}.", index, #struct_ident_str, #output_len),
+                }
+            }
+
+            fn input_name_at(&self, index: usize) -> &str {
+                match index {
+                    #(#input_indices => #input_names,)*
+                    _ => panic!("Index {} out of bounds for {} with input len {}.", index, #struct_ident_str, #input_len),
+                }
+            }
+
+            fn output_name_at(&self, index: usize) -> &str {
+                match index {
+                    #(#output_indices => #output_names,)*
+                    _ => panic!("Index {} out of bounds for {} with output len {}.", index, #struct_ident_str, #output_len),
+                }
+            }
+
+            fn input_len(&self) -> usize {
+                #input_len
+            }
+
+            fn output_len(&self) -> usize {
+                #output_len
+            }
         }
     }
     .into()
@@ -74,18 +147,14 @@ fn get_generic_bounds(fields: Vec<Field>) -> Vec<WherePredicate> {
         .map(|f| match &f.ty {
             Type::Path(path) => match &path.path.segments.first().unwrap().arguments {
                 syn::PathArguments::AngleBracketed(angle) => match angle.args.first().unwrap() {
-                    syn::GenericArgument::Type(generic) => match generic {
-                        Type::Path(t_path) => {
-                            let ident = t_path.path.segments.first().unwrap().ident.clone();
-                            let cond: TokenStream = quote::quote! {
-                                #ident: Clone + 'static
-                            }
-                            .into();
-                            let cond_ast: WherePredicate = syn::parse(cond.clone()).unwrap();
-                            cond_ast
+                    syn::GenericArgument::Type(generic) => {
+                        let cond: TokenStream = quote::quote! {
+                            #generic: Clone + 'static
                         }
-                        _ => panic!("{}", generic_err(f.clone().ident.unwrap())),
-                    },
+                        .into();
+                        let cond_ast: WherePredicate = syn::parse(cond.clone()).unwrap();
+                        cond_ast
+                    }
                     _ => panic!("{}", generic_err(f.clone().ident.unwrap())),
                 },
                 _ => panic!("{}", generic_err(f.clone().ident.unwrap())),
@@ -115,12 +184,9 @@ fn validate_struct_field(strct: DataStruct, ty: &str, mcro: &str) -> Vec<Field>
                 _ => false,
             };
             let helper_macro_valid = match f.attrs.get(0) {
-                Some(attr) => match &attr.meta {
-                    syn::Meta::Path(p) => match p.segments.first() {
-                        Some(segment) => segment.ident.to_string() == mcro,
-                        None => false,
-                    },
-                    _ => false,
+                Some(attr) => match attr.path().segments.first() {
+                    Some(segment) => segment.ident.to_string() == mcro,
+                    None => false,
                 },
                 None => false,
             };
@@ -131,3 +197,22 @@ fn validate_struct_field(strct: DataStruct, ty: &str, mcro: &str) -> Vec<Field>
         })
         .collect()
 }
+
+/// Whether the field is annotated with `#[output(optional)]`.
+fn is_optional(field: &Field) -> bool {
+    let mut optional = false;
+    if let Some(attr) = field.attrs.get(0) {
+        if let syn::Meta::List(_) = attr.meta {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("optional") {
+                    optional = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("Unsupported output option, expected `optional`."))
+                }
+            })
+            .unwrap_or_else(|err| panic!("{}", err));
+        }
+    }
+    optional
+}
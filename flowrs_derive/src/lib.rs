@@ -1,12 +1,28 @@
 mod connectable;
+mod node;
 
 use proc_macro::TokenStream;
 
 use connectable::impl_connectable_trait;
+use node::impl_node_trait;
 
+/// Implements `RuntimeConnectable` for a struct from its fields annotated with `#[input]` and
+/// `#[output]`. Outputs annotated with `#[output(optional)]` are reported by
+/// `output_is_optional`, so a flow accepts them while unconnected.
 #[proc_macro_derive(Connectable, attributes(input, output))]
 pub fn connectable_derive_macro(item: TokenStream) -> TokenStream {
     let ast = syn::parse(item).unwrap();
 
     impl_connectable_trait(ast)
 }
+
+/// Generates `update` for an `impl Node for ...` block from methods annotated with
+/// `#[on_input(port)]`. The generated `update` drains each annotated input, in the order of the
+/// handlers, and passes every element to its handler. Handlers returning a `Result` stop the
+/// update on the first error.
+#[proc_macro_attribute]
+pub fn node(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let ast = syn::parse(item).unwrap();
+
+    impl_node_trait(ast)
+}
@@ -0,0 +1,93 @@
+use proc_macro::TokenStream;
+use syn::{FnArg, Ident, ImplItem, ImplItemFn, ItemImpl, ReturnType};
+
+/// A method annotated with `#[on_input(port)]`.
+struct Handler {
+    input: Ident,
+    method: ImplItemFn,
+}
+
+pub fn impl_node_trait(mut ast: ItemImpl) -> TokenStream {
+    if ast.trait_.is_none() {
+        panic!("The #[node] macro only works for `impl Node for ...` blocks.");
+    }
+
+    let mut handlers = Vec::new();
+    let mut items = Vec::new();
+    for item in ast.items.drain(..) {
+        match item {
+            ImplItem::Fn(method) if method.sig.ident == "update" => {
+                panic!(
+                    "The #[node] macro generates `update`, use #[on_input(...)] handlers instead."
+                )
+            }
+            ImplItem::Fn(method) if method.attrs.iter().any(is_on_input) => {
+                handlers.push(handler(method))
+            }
+            item => items.push(item),
+        }
+    }
+
+    let calls = handlers.iter().map(|handler| {
+        let input = &handler.input;
+        let ident = &handler.method.sig.ident;
+        let call = match handler.method.sig.output {
+            ReturnType::Default => quote::quote! { self.#ident(elem); },
+            ReturnType::Type(..) => quote::quote! { self.#ident(elem)?; },
+        };
+        quote::quote! {
+            while let Ok(elem) = self.#input.next_elem() {
+                #call
+            }
+        }
+    });
+    let update: ImplItem = syn::parse2(quote::quote! {
+        fn update(&self) -> ::core::result::Result<(), flowrs::node::UpdateError> {
+            #(#calls)*
+            Ok(())
+        }
+    })
+    .unwrap();
+    items.push(update);
+    ast.items = items;
+
+    let (impl_generics, _, where_clause) = ast.generics.split_for_impl();
+    let self_ty = &ast.self_ty;
+    let methods = handlers.iter().map(|handler| &handler.method);
+    quote::quote! {
+        impl #impl_generics #self_ty #where_clause {
+            #(#methods)*
+        }
+
+        #ast
+    }
+    .into()
+}
+
+fn is_on_input(attr: &syn::Attribute) -> bool {
+    attr.path().is_ident("on_input")
+}
+
+fn handler(mut method: ImplItemFn) -> Handler {
+    let name = method.sig.ident.to_string();
+    let attr = method.attrs.iter().find(|attr| is_on_input(attr)).unwrap();
+    let input: Ident = attr.parse_args().unwrap_or_else(|_| {
+        panic!(
+            "Handler {} needs the input it handles, e.g. #[on_input(input_1)].",
+            name
+        )
+    });
+    method.attrs.retain(|attr| !is_on_input(attr));
+
+    let mut args = method.sig.inputs.iter();
+    let valid = matches!(args.next(), Some(FnArg::Receiver(_)))
+        && matches!(args.next(), Some(FnArg::Typed(_)))
+        && args.next().is_none();
+    if !valid {
+        panic!(
+            "Handler {} must take `&self` and the element, e.g. `fn {}(&self, elem: T)`.",
+            name, name
+        );
+    }
+    Handler { input, method }
+}
@@ -1,10 +1,31 @@
-mod nodes;
+// Lets `#[derive(Connectable)]` refer to `flowrs` from within this crate.
+extern crate self as flowrs;
+
+#[macro_use]
+mod trace;
+
+pub mod nodes;
 mod sched;
+pub mod testing;
 
 pub use self::nodes::connection;
+pub use self::nodes::function;
+pub use self::nodes::lifecycle;
+pub use self::nodes::lineage;
 pub use self::nodes::node;
+pub use self::nodes::subflow;
 
+pub use self::sched::checkpoint;
+pub use self::sched::definition;
+pub use self::sched::editor;
 pub use self::sched::executor;
 pub use self::sched::flow;
+pub use self::sched::metrics;
+pub use self::sched::migration;
+pub use self::sched::recording;
+pub use self::sched::registry;
 pub use self::sched::scheduler;
+pub use self::sched::validation;
 pub use self::sched::version;
+
+pub use flowrs_derive::node;
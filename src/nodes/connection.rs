@@ -3,11 +3,21 @@ use std::{
     fmt,
     rc::Rc,
     sync::{
+        atomic::{AtomicUsize, Ordering},
         mpsc::{channel, Receiver, RecvError, SendError, Sender, TryRecvError},
         Arc, Mutex,
     },
 };
-use crate::node::{State, Context};
+use crate::{
+    lineage::{self, Envelope},
+    node::{Context, State},
+};
+
+/// An element together with its lineage, if lineage is recorded.
+type Entry<I> = (I, Option<Arc<Envelope>>);
+
+/// Observes elements sent to an edge through an [`Output`], see [`Edge::set_tap`].
+type Tap<I> = Arc<dyn Fn(&I) -> Box<dyn FnOnce()> + Send + Sync>;
 
 #[derive(Debug)]
 pub enum ConnectError<I> {
@@ -15,6 +25,7 @@ pub enum ConnectError<I> {
     RecvErr(RecvError),
     TryRecvErr(TryRecvError),
     ChanErr(ChannelError),
+    NotConnected,
 }
 
 #[derive(Debug, Clone)]
@@ -53,79 +64,245 @@ impl fmt::Display for ChannelError {
     }
 }
 
-#[derive(Debug)]
+/// A queue between nodes.
+///
+/// Clones share the queue, so elements can be sent and received through any clone, not only
+/// through the edge owned by the receiving node. Receiving is serialized by a lock. Sharing lets
+/// a [`Flow`](crate::flow::Flow) checkpoint queued elements and move them when it replaces a
+/// node, and lets tests inspect what a node receives. An element is received by exactly one of
+/// the clones, so a node only sees what no other clone took first.
 pub struct Edge<I> {
-    sender: Sender<I>,
-    receiver: Option<Receiver<I>>,
+    sender: Sender<Entry<I>>,
+    receiver: Arc<Mutex<Receiver<Entry<I>>>>,
+    len: Arc<AtomicUsize>,
+    tap: Arc<Mutex<Option<Tap<I>>>>,
 }
 
 impl<I> Clone for Edge<I> {
     fn clone(&self) -> Self {
         Self {
             sender: self.sender.clone(),
-            receiver: None,
+            receiver: self.receiver.clone(),
+            len: self.len.clone(),
+            tap: self.tap.clone(),
         }
     }
 }
 
+impl<I> fmt::Debug for Edge<I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Edge").field("len", &self.len()).finish()
+    }
+}
+
+impl<I> Default for Edge<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<I> Edge<I> {
     pub fn new() -> Self {
         let (sender, receiver) = channel();
         Self {
             sender,
-            receiver: Some(receiver),
+            receiver: Arc::new(Mutex::new(receiver)),
+            len: Arc::new(AtomicUsize::new(0)),
+            tap: Arc::new(Mutex::new(None)),
         }
     }
 
+    /// Observes every element an [`Output`] sends to this edge, replacing a previous tap.
+    ///
+    /// `tap` is called with each element before it is sent and returns a function that is called
+    /// once the element was sent successfully. Elements sent to the edge directly are not
+    /// observed.
+    pub fn set_tap<F, S>(&self, tap: F)
+    where
+        F: Fn(&I) -> S + Send + Sync + 'static,
+        S: FnOnce() + 'static,
+    {
+        *self.tap.lock().unwrap() = Some(Arc::new(move |elem: &I| -> Box<dyn FnOnce()> {
+            Box::new(tap(elem))
+        }));
+    }
+
+    pub fn clear_tap(&self) {
+        *self.tap.lock().unwrap() = None;
+    }
+
     pub fn send(&self, elem: I) -> Result<(), ConnectError<I>> {
-        Ok(self.sender.send(elem)?)
+        self.send_entry((elem, None))
+    }
+
+    pub(crate) fn send_entry(&self, entry: Entry<I>) -> Result<(), ConnectError<I>> {
+        self.len.fetch_add(1, Ordering::SeqCst);
+        self.sender.send(entry).map_err(|SendError((elem, _))| {
+            self.len.fetch_sub(1, Ordering::SeqCst);
+            SendError(elem).into()
+        })
     }
 
     pub fn has_next(&self) -> bool {
-        self.receiver.as_ref().iter().peekable().peek().is_some()
+        !self.is_empty()
+    }
+
+    /// The number of elements currently queued on this edge.
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::SeqCst)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
     pub fn next_elem(&self) -> Result<I, ConnectError<I>> {
-        Ok(self
-            .receiver
-            .as_ref()
-            .expect("Only the Node that created this edge can receive from it.")
-            .try_recv()?)
+        self.next_with_envelope().map(|(elem, _)| elem)
+    }
+
+    /// Like [`Edge::next_elem`], but also returns the lineage of the element if it is recorded,
+    /// see [`LineageRecorder`](crate::lineage::LineageRecorder).
+    pub fn next_with_envelope(&self) -> Result<(I, Option<Envelope>), ConnectError<I>> {
+        let (elem, envelope) = self.next_entry()?;
+        if let Some(envelope) = &envelope {
+            lineage::received(envelope.id);
+        }
+        Ok((elem, envelope.map(|envelope| envelope.as_ref().clone())))
+    }
+
+    /// Receives the next element without noting its lineage as received by a node.
+    pub(crate) fn next_entry(&self) -> Result<Entry<I>, ConnectError<I>> {
+        let entry = self.receiver.lock().unwrap().try_recv()?;
+        self.len.fetch_sub(1, Ordering::SeqCst);
+        Ok(entry)
     }
 }
 
 pub type Input<I> = Edge<I>;
 
-#[derive(Clone)]
 pub struct Output<T>{
     edge: Arc<Mutex<Option<Edge<T>>>>,
     context: State<Context>
 }
 
+impl<T> Clone for Output<T> {
+    fn clone(&self) -> Self {
+        Self {
+            edge: self.edge.clone(),
+            context: self.context.clone(),
+        }
+    }
+}
+
 impl<O> Output<O> {
     pub fn new(context: State<Context> ) -> Self {
         Self{
             edge: Arc::new(Mutex::new(None)),
-            context: context
+            context,
         }
     }
 
+    /// Sends `elem` to the connected successor.
+    ///
+    /// If no successor is connected, `elem` is dropped and this fails with
+    /// [`ConnectError::NotConnected`]. Nodes ignore this error for outputs declared with
+    /// `#[output(optional)]`, which may stay unconnected, see
+    /// [`RuntimeConnectable::output_is_optional`].
     pub fn send(&mut self, elem: O) -> Result<(), ConnectError<O>> {
-        let res = self.edge
-            .lock()
-            .unwrap()
-            .as_mut()
-            .expect("You attempted to send to an output where no succesor Node is connected.")
-            .send(elem);
+        // Neither the output nor the tap stay locked while the tap runs.
+        let edge = self.edge.lock().unwrap().clone();
+        match edge {
+            Some(edge) => {
+                let tap = edge.tap.lock().unwrap().clone();
+                let sent = tap.map(|tap| tap(&elem));
+                let envelope = self.context.0.lock().unwrap().lineage().map(|l| l.emit());
+                edge.send_entry((elem, envelope))?;
+                if let Some(sent) = sent {
+                    sent();
+                }
+            }
+            None => {
+                trace!("Dropped element sent to an unconnected output.");
+                return Err(ConnectError::NotConnected);
+            }
+        }
 
-            self.context.0.lock().unwrap().on_change();
+        self.context.0.lock().unwrap().on_change();
 
-        res
+        Ok(())
     }
 
     pub fn set(&mut self, edge: Edge<O>) {
         let _ = self.edge.lock().unwrap().insert(edge);
     }
+
+    pub fn unset(&mut self) {
+        let _ = self.edge.lock().unwrap().take();
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.edge.lock().unwrap().is_some()
+    }
+}
+
+/// Type erased view on an [`Output`], independent of its element type.
+pub trait AnyOutput {
+    fn is_connected(&self) -> bool;
+
+    /// Moves the connection of this output to `other`, if `other` is an output of the same type.
+    /// Returns whether a connection was moved.
+    fn move_to(&self, other: Rc<dyn Any>) -> bool;
+}
+
+impl<O: 'static> AnyOutput for Output<O> {
+    fn is_connected(&self) -> bool {
+        Output::is_connected(self)
+    }
+
+    fn move_to(&self, other: Rc<dyn Any>) -> bool {
+        match other.downcast::<Output<O>>() {
+            Ok(other) => match self.edge.lock().unwrap().take() {
+                Some(edge) => {
+                    other.as_ref().clone().set(edge);
+                    true
+                }
+                None => false,
+            },
+            Err(_) => false,
+        }
+    }
+}
+
+/// Type erased view on an [`Input`], independent of its element type.
+pub trait AnyInput {
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool;
+
+    /// Moves all queued elements to `other`, if `other` is an input of the same type. Returns
+    /// whether `other` had the right type.
+    fn drain_into(&self, other: Rc<dyn Any>) -> bool;
+}
+
+impl<I: 'static> AnyInput for Input<I> {
+    fn len(&self) -> usize {
+        Edge::len(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        Edge::is_empty(self)
+    }
+
+    fn drain_into(&self, other: Rc<dyn Any>) -> bool {
+        match other.downcast::<Input<I>>() {
+            Ok(other) => {
+                while let Ok(entry) = self.next_entry() {
+                    let _ = other.send_entry(entry);
+                }
+                true
+            }
+            Err(_) => false,
+        }
+    }
 }
 
 pub fn connect<I>(mut lhs: Output<I>, rhs: Input<I>) {
@@ -135,4 +312,11 @@ pub fn connect<I>(mut lhs: Output<I>, rhs: Input<I>) {
 pub trait RuntimeConnectable {
     fn input_at(&self, index: usize) -> Rc<dyn Any>;
     fn output_at(&self, index: usize) -> Rc<dyn Any>;
+    fn dyn_input_at(&self, index: usize) -> Rc<dyn AnyInput>;
+    fn dyn_output_at(&self, index: usize) -> Rc<dyn AnyOutput>;
+    fn output_is_optional(&self, index: usize) -> bool;
+    fn input_name_at(&self, index: usize) -> &str;
+    fn output_name_at(&self, index: usize) -> &str;
+    fn input_len(&self) -> usize;
+    fn output_len(&self) -> usize;
 }
@@ -0,0 +1,171 @@
+//! Nodes built from closures, for nodes that would otherwise be a struct, a constructor and an
+//! `update` draining each input.
+
+use std::{
+    any::Any,
+    collections::VecDeque,
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+use flowrs_derive::Connectable;
+
+use crate::{
+    connection::{Input, Output, RuntimeConnectable},
+    node::{Context, Node, State, UpdateError},
+};
+
+/// The elements of two inputs that are waiting for a partner.
+pub(crate) type Pending<A, B> = Mutex<(VecDeque<A>, VecDeque<B>)>;
+
+/// Moves all queued elements of `lhs` and `rhs` to `pending` and returns the elements that
+/// could be paired, in arrival order.
+pub(crate) fn pair<A, B>(pending: &Pending<A, B>, lhs: &Input<A>, rhs: &Input<B>) -> Vec<(A, B)> {
+    let mut pending = pending.lock().unwrap();
+    while let Ok(elem) = lhs.next_elem() {
+        pending.0.push_back(elem);
+    }
+    while let Ok(elem) = rhs.next_elem() {
+        pending.1.push_back(elem);
+    }
+    let len = pending.0.len().min(pending.1.len());
+    let (lhs, rhs) = &mut *pending;
+    lhs.drain(..len).zip(rhs.drain(..len)).collect()
+}
+
+type Fn1<I, O> = Arc<dyn Fn(I) -> O + Send + Sync>;
+type Fn2<I1, I2, O> = Arc<dyn Fn(I1, I2) -> O + Send + Sync>;
+
+/// A node sending the result of a closure for each input element.
+#[derive(Connectable)]
+pub struct FnNode<I, O> {
+    name: String,
+    f: Fn1<I, O>,
+
+    #[input]
+    pub input: Input<I>,
+    #[output]
+    pub output: Output<O>,
+}
+
+impl<I, O> FnNode<I, O> {
+    pub fn new<F>(name: &str, context: State<Context>, f: F) -> Self
+    where
+        F: Fn(I) -> O + Send + Sync + 'static,
+    {
+        Self {
+            name: name.into(),
+            f: Arc::new(f),
+            input: Input::new(),
+            output: Output::new(context),
+        }
+    }
+}
+
+impl<I, O> Node for FnNode<I, O>
+where
+    I: Send + 'static,
+    O: Send + 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn update(&self) -> Result<(), UpdateError> {
+        while let Ok(elem) = self.input.next_elem() {
+            let _ = self.output.clone().send((self.f)(elem));
+        }
+        Ok(())
+    }
+}
+
+/// A node sending the result of a closure for the n-th elements of both inputs.
+///
+/// Elements are buffered until an element arrives on the other input, like in
+/// [`Zip`](crate::nodes::std::Zip).
+#[derive(Connectable)]
+pub struct FnNode2<I1, I2, O> {
+    name: String,
+    f: Fn2<I1, I2, O>,
+    pending: Pending<I1, I2>,
+
+    #[input]
+    pub input_1: Input<I1>,
+    #[input]
+    pub input_2: Input<I2>,
+    #[output]
+    pub output: Output<O>,
+}
+
+impl<I1, I2, O> FnNode2<I1, I2, O> {
+    pub fn new<F>(name: &str, context: State<Context>, f: F) -> Self
+    where
+        F: Fn(I1, I2) -> O + Send + Sync + 'static,
+    {
+        Self {
+            name: name.into(),
+            f: Arc::new(f),
+            pending: Mutex::new((VecDeque::new(), VecDeque::new())),
+            input_1: Input::new(),
+            input_2: Input::new(),
+            output: Output::new(context),
+        }
+    }
+}
+
+impl<I1, I2, O> Node for FnNode2<I1, I2, O>
+where
+    I1: Send + 'static,
+    I2: Send + 'static,
+    O: Send + 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn update(&self) -> Result<(), UpdateError> {
+        for (lhs, rhs) in pair(&self.pending, &self.input_1, &self.input_2) {
+            let _ = self.output.clone().send((self.f)(lhs, rhs));
+        }
+        Ok(())
+    }
+}
+
+/// A node calling a closure for each input element, without outputs.
+#[derive(Connectable)]
+pub struct FnSink<I> {
+    name: String,
+    f: Fn1<I, ()>,
+
+    #[input]
+    pub input: Input<I>,
+}
+
+impl<I> FnSink<I> {
+    pub fn new<F>(name: &str, f: F) -> Self
+    where
+        F: Fn(I) + Send + Sync + 'static,
+    {
+        Self {
+            name: name.into(),
+            f: Arc::new(f),
+            input: Input::new(),
+        }
+    }
+}
+
+impl<I> Node for FnSink<I>
+where
+    I: Send + 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn update(&self) -> Result<(), UpdateError> {
+        while let Ok(elem) = self.input.next_elem() {
+            (self.f)(elem);
+        }
+        Ok(())
+    }
+}
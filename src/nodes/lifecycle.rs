@@ -0,0 +1,73 @@
+use std::fmt;
+use thiserror::Error;
+
+/// The lifecycle state of a node that is managed by a [`Flow`](crate::flow::Flow).
+///
+/// Legal transitions are:
+///
+/// ```text
+/// Created -> Initialized -> Ready -> Running -> ShutDown
+///    |            |           |         |  ^
+///    +------------+-----------+---------+--+--> Failed -> ShutDown
+/// ```
+///
+/// `Running -> Running` is legal as well, since every update keeps the node running.
+/// Update errors are treated as per-message errors and do not fail a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeLifecycle {
+    Created,
+    Initialized,
+    Ready,
+    Running,
+    Failed,
+    ShutDown,
+}
+
+impl fmt::Display for NodeLifecycle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NodeLifecycle::Created => write!(f, "Created"),
+            NodeLifecycle::Initialized => write!(f, "Initialized"),
+            NodeLifecycle::Ready => write!(f, "Ready"),
+            NodeLifecycle::Running => write!(f, "Running"),
+            NodeLifecycle::Failed => write!(f, "Failed"),
+            NodeLifecycle::ShutDown => write!(f, "ShutDown"),
+        }
+    }
+}
+
+impl NodeLifecycle {
+    pub fn can_transition_to(&self, next: NodeLifecycle) -> bool {
+        use NodeLifecycle::*;
+        matches!(
+            (*self, next),
+            (Created, Initialized)
+                | (Initialized, Ready)
+                | (Ready, Running)
+                | (Running, Running)
+                | (Initialized | Ready | Running | Failed, ShutDown)
+                | (Created | Initialized | Ready | Running, Failed)
+        )
+    }
+
+    /// Moves to `next` if the transition is legal, otherwise leaves the state untouched.
+    pub fn transition(&mut self, node: &str, next: NodeLifecycle) -> Result<(), LifecycleError> {
+        if !self.can_transition_to(next) {
+            return Err(LifecycleError {
+                node: node.into(),
+                from: *self,
+                to: next,
+            });
+        }
+        *self = next;
+        Ok(())
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Illegal lifecycle transition for node '{node}' from {from} to {to}.")]
+pub struct LifecycleError {
+    pub node: String,
+    pub from: NodeLifecycle,
+    pub to: NodeLifecycle,
+}
@@ -0,0 +1,175 @@
+use serde::Serialize;
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::SystemTime,
+};
+
+/// Identifies a message recorded by a [`LineageRecorder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct MessageId(u64);
+
+impl fmt::Display for MessageId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "m{}", self.0)
+    }
+}
+
+/// Metadata travelling with a message along an edge.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Envelope {
+    pub id: MessageId,
+    /// The node that sent the message, `None` if it was sent from outside of a node update.
+    pub origin: Option<String>,
+    pub timestamp: SystemTime,
+    /// The messages the origin received before sending this one, see [`LineageRecorder`].
+    pub parents: Vec<MessageId>,
+}
+
+/// Records the lineage of all messages sent through outputs of a [`Context`](crate::node::Context)
+/// with lineage enabled, see [`Context::set_lineage`](crate::node::Context::set_lineage).
+///
+/// A message sent during a node update gets as parents the messages the node received earlier in
+/// the same update since it last sent a message. If it sends several messages in a row, they all
+/// share the same parents. Clones of the recorder share the recorded messages.
+///
+/// A recorder keeps a bounded number of messages and evicts the oldest ones first, so paths
+/// through evicted messages end early. Use [`LineageRecorder::clear`] to drop all messages.
+#[derive(Debug, Clone)]
+pub struct LineageRecorder {
+    next_id: Arc<AtomicU64>,
+    capacity: usize,
+    messages: Arc<Mutex<BTreeMap<MessageId, Envelope>>>,
+}
+
+impl Default for LineageRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LineageRecorder {
+    /// The number of messages kept by a recorder created with [`LineageRecorder::new`].
+    pub const DEFAULT_CAPACITY: usize = 10_000;
+
+    pub fn new() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
+
+    /// Creates a recorder that keeps the `capacity` most recent messages.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            next_id: Arc::new(AtomicU64::new(0)),
+            capacity,
+            messages: Arc::new(Mutex::new(BTreeMap::new())),
+        }
+    }
+
+    /// Creates and records the envelope of a message that is about to be sent.
+    pub(crate) fn emit(&self) -> Arc<Envelope> {
+        let (origin, parents) = CURRENT.with(|current| match &mut *current.borrow_mut() {
+            Some(update) => {
+                update.sent = true;
+                (Some(update.node.clone()), update.received.clone())
+            }
+            None => (None, Vec::new()),
+        });
+        let envelope = Envelope {
+            id: MessageId(self.next_id.fetch_add(1, Ordering::SeqCst)),
+            origin,
+            timestamp: SystemTime::now(),
+            parents,
+        };
+        let mut messages = self.messages.lock().unwrap();
+        messages.insert(envelope.id, envelope.clone());
+        // Ids increase over time, so the first messages are the oldest.
+        while messages.len() > self.capacity {
+            messages.pop_first();
+        }
+        Arc::new(envelope)
+    }
+
+    pub fn envelope(&self, id: MessageId) -> Option<Envelope> {
+        self.messages.lock().unwrap().get(&id).cloned()
+    }
+
+    /// All recorded messages that were not evicted yet, oldest first.
+    pub fn messages(&self) -> Vec<Envelope> {
+        self.messages.lock().unwrap().values().cloned().collect()
+    }
+
+    /// Drops all recorded messages.
+    pub fn clear(&self) {
+        self.messages.lock().unwrap().clear();
+    }
+
+    /// The message `id` together with all messages it was derived from, oldest first.
+    pub fn path(&self, id: MessageId) -> Vec<Envelope> {
+        let messages = self.messages.lock().unwrap();
+        let mut visited = BTreeSet::new();
+        let mut pending = vec![id];
+        while let Some(id) = pending.pop() {
+            if let Some(envelope) = messages.get(&id) {
+                if visited.insert(id) {
+                    pending.extend(&envelope.parents);
+                }
+            }
+        }
+        // Ids increase over time, so parents always come first.
+        visited
+            .into_iter()
+            .map(|id| messages[&id].clone())
+            .collect()
+    }
+}
+
+/// The lineage state of the node update running on the current thread.
+struct Update {
+    node: String,
+    received: Vec<MessageId>,
+    sent: bool,
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Update>> = const { RefCell::new(None) };
+}
+
+/// Marks the current thread as updating `node` until the guard is dropped.
+pub(crate) fn enter(node: &str) -> UpdateGuard {
+    let previous = CURRENT.with(|current| {
+        current.borrow_mut().replace(Update {
+            node: node.into(),
+            received: Vec::new(),
+            sent: false,
+        })
+    });
+    UpdateGuard(previous)
+}
+
+/// Restores the update that was running before, e.g. the update of a subflow.
+pub(crate) struct UpdateGuard(Option<Update>);
+
+impl Drop for UpdateGuard {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        CURRENT.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+/// Notes that the node updated on the current thread received the message `id`.
+pub(crate) fn received(id: MessageId) {
+    CURRENT.with(|current| {
+        if let Some(update) = &mut *current.borrow_mut() {
+            if update.sent {
+                update.received.clear();
+                update.sent = false;
+            }
+            update.received.push(id);
+        }
+    });
+}
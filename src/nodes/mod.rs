@@ -1,2 +1,7 @@
 pub mod connection;
+pub mod function;
+pub mod lifecycle;
+pub mod lineage;
 pub mod node;
+pub mod std;
+pub mod subflow;
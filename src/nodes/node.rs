@@ -1,14 +1,65 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use schemars::JsonSchema;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
 use thiserror::Error;
 use anyhow::Result;
 
+use crate::lineage::LineageRecorder;
+
 pub trait ChangeObserver: Send {
     fn on_change(&mut self);
+
+    /// Called when a node asks for an update at `at`, see [`Context::request_update_at`].
+    fn on_update_requested(&mut self, _at: Instant) {}
 }
 
-#[derive(Clone)]
+/// Where nodes take the current time from, see [`Context::now`].
+///
+/// A manual clock only moves when it is advanced, which makes nodes acting on time deterministic
+/// in tests. Clones share the time of a manual clock. The executor runs updates requested with
+/// [`Context::request_update_at`] once they are due on the clock of the context, so on a manual
+/// clock only after it was advanced past them.
+#[derive(Debug, Clone, Default)]
+pub struct Clock(Option<Arc<Mutex<Instant>>>);
+
+impl Clock {
+    pub fn system() -> Self {
+        Self(None)
+    }
+
+    /// A clock that stands still at the current time until it is advanced.
+    pub fn manual() -> Self {
+        Self(Some(Arc::new(Mutex::new(Instant::now()))))
+    }
+
+    pub fn now(&self) -> Instant {
+        match &self.0 {
+            Some(now) => *now.lock().unwrap(),
+            None => Instant::now(),
+        }
+    }
+
+    /// Moves a manual clock forward by `duration`.
+    ///
+    /// # Panics
+    ///
+    /// If this is the system clock.
+    pub fn advance(&self, duration: Duration) {
+        let now = self.0.as_ref().expect("Only a manual clock can be advanced.");
+        *now.lock().unwrap() += duration;
+    }
+}
+
+#[derive(Clone, Default)]
 pub struct Context {
     change_observer: Option<Arc<Mutex<dyn ChangeObserver>>>,
+    lineage: Option<LineageRecorder>,
+    clock: Clock,
+    epoch: u64,
 }
 
 impl Context {
@@ -18,24 +69,110 @@ impl Context {
         }
     }
 
+    /// Asks the executor to run an epoch at `at`, even if nothing changes until then. Nodes that
+    /// act on time, e.g. [`Debounce`](crate::nodes::std::Debounce), use this to be updated again.
+    pub fn request_update_at(&self, at: Instant) {
+        if let Some(so) = &self.change_observer {
+            so.lock().unwrap().on_update_requested(at);
+        }
+    }
+
     pub fn new() -> Self {
         Self {
             change_observer: None,
+            lineage: None,
+            clock: Clock::system(),
+            epoch: 0,
         }
     }
 
     pub fn set_observer(&mut self, observer: Arc<Mutex<dyn ChangeObserver>>) {
         self.change_observer = Some(observer);
     }
+
+    /// Records the lineage of all messages sent through outputs created with this context.
+    pub fn set_lineage(&mut self, recorder: LineageRecorder) {
+        self.lineage = Some(recorder);
+    }
+
+    pub fn lineage(&self) -> Option<&LineageRecorder> {
+        self.lineage.as_ref()
+    }
+
+    /// The current time of the clock nodes act on, see [`Clock`].
+    pub fn now(&self) -> Instant {
+        self.clock.now()
+    }
+
+    pub fn clock(&self) -> Clock {
+        self.clock.clone()
+    }
+
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+    }
+
+    /// The epoch the executor is currently in, `0` before the first epoch.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    pub fn set_epoch(&mut self, epoch: u64) {
+        self.epoch = epoch;
+    }
 }
 
 pub trait Node : Send + 'static {
     fn name(&self) -> &str;
 
-    fn on_init(&self) -> Result<(), InitError>;
-    fn on_ready(&self) -> Result<(), ReadyError>;
-    fn on_shutdown(&self) -> Result<(), ShutdownError>;
+    /// Called once before the flow is started. Nodes without setup keep the default.
+    fn on_init(&self) -> Result<(), InitError> {
+        Ok(())
+    }
+
+    /// Called once all nodes of the flow are initialized.
+    fn on_ready(&self) -> Result<(), ReadyError> {
+        Ok(())
+    }
+
+    /// Called when the flow stops.
+    fn on_shutdown(&self) -> Result<(), ShutdownError> {
+        Ok(())
+    }
+
     fn update(&self) -> Result<(), UpdateError>;
+
+    /// Exports the internal state of the node, e.g. to migrate it to a replacement node or to
+    /// checkpoint it. Nodes without state return `None`.
+    fn export_state(&self) -> Result<Option<Value>, StateError> {
+        Ok(None)
+    }
+
+    /// Imports state previously exported by [`Node::export_state`].
+    fn import_state(&self, _state: Value) -> Result<(), StateError> {
+        Ok(())
+    }
+
+    /// Applies new props while the node is part of a running flow. Nodes that do not support
+    /// reconfiguration reject all props.
+    fn on_config_change(&self, _new_props: Value) -> Result<(), ConfigError> {
+        Err(ConfigError::Unsupported {
+            node: self.name().into(),
+        })
+    }
+}
+
+/// A node that is constructed from typed properties.
+///
+/// Nodes registered with
+/// [`NodeRegistry::register_typed_node`](crate::registry::NodeRegistry::register_typed_node) get
+/// their props validated against the JSON Schema of [`FromProperties::Properties`] before they
+/// are constructed.
+pub trait FromProperties: Node + Sized {
+    type Properties: DeserializeOwned + JsonSchema;
+
+    fn from_properties(name: &str, context: State<Context>, props: Self::Properties)
+        -> Result<Self>;
 }
 
 #[derive(Error, Debug)]
@@ -65,6 +202,37 @@ pub enum ShutdownError {
     Other(#[from] anyhow::Error)
 } 
 
+#[derive(Error, Debug)]
+pub enum StateError {
+
+    #[error("Node {node:?} can not import the given state. Message: {message:?}")]
+    Incompatible {
+        node: String,
+        message: String,
+    },
+
+    #[error(transparent)]
+    Other(#[from] anyhow::Error)
+} 
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+
+    #[error("Node {node:?} does not support changing its configuration.")]
+    Unsupported {
+        node: String,
+    },
+
+    #[error("Node {node:?} rejected the given configuration. Message: {message:?}")]
+    Rejected {
+        node: String,
+        message: String,
+    },
+
+    #[error(transparent)]
+    Other(#[from] anyhow::Error)
+}
+
 #[derive(Debug)]
 pub struct SequenceError {
     pub node: String,
@@ -92,6 +260,22 @@ impl<S: Clone> State<S> {
     }
 }
 
+impl<S: Clone + Serialize> State<S> {
+    /// Serializes the inner value, e.g. for [`Node::export_state`].
+    pub fn to_value(&self) -> Result<Value, StateError> {
+        serde_json::to_value(&*self.0.lock().unwrap()).map_err(|err| StateError::Other(err.into()))
+    }
+}
+
+impl<S: Clone + DeserializeOwned> State<S> {
+    /// Replaces the inner value with a deserialized one, e.g. for [`Node::import_state`].
+    pub fn load(&self, value: Value) -> Result<(), StateError> {
+        let inner = serde_json::from_value(value).map_err(|err| StateError::Other(err.into()))?;
+        *self.0.lock().unwrap() = inner;
+        Ok(())
+    }
+}
+
 impl<S: Clone> Clone for State<S> {
     fn clone(&self) -> Self {
         Self(self.0.clone())
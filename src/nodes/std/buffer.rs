@@ -0,0 +1,184 @@
+use anyhow::{anyhow, Result};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::{
+    any::Any,
+    rc::Rc,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use flowrs_derive::Connectable;
+
+use crate::{
+    connection::{Input, Output, RuntimeConnectable},
+    node::{Context, FromProperties, Node, State, UpdateError},
+};
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct BufferProperties {
+    /// The number of elements per batch, at least one.
+    pub capacity: usize,
+}
+
+/// Collects input elements and sends them in batches of `capacity` elements.
+#[derive(Connectable)]
+pub struct Buffer<T> {
+    name: String,
+    capacity: usize,
+    buffer: Mutex<Vec<T>>,
+
+    #[input]
+    pub input: Input<T>,
+    #[output]
+    pub output: Output<Vec<T>>,
+}
+
+impl<T> Buffer<T> {
+    /// Creates a buffer sending batches of `capacity` elements.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is zero.
+    pub fn new(name: &str, context: State<Context>, capacity: usize) -> Self {
+        assert!(capacity > 0, "A buffer needs a capacity of at least one.");
+        Self {
+            name: name.into(),
+            capacity,
+            // Batches grow as elements arrive, so huge capacities do not allocate up front.
+            buffer: Mutex::new(Vec::new()),
+            input: Input::new(),
+            output: Output::new(context),
+        }
+    }
+
+    /// The number of elements waiting for the batch to be full.
+    pub fn len(&self) -> usize {
+        self.buffer.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Node for Buffer<T>
+where
+    T: Send + 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn update(&self) -> Result<(), UpdateError> {
+        let mut buffer = self.buffer.lock().unwrap();
+        while let Ok(elem) = self.input.next_elem() {
+            buffer.push(elem);
+            if buffer.len() == self.capacity {
+                let _ = self.output.clone().send(std::mem::take(&mut *buffer));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T> FromProperties for Buffer<T>
+where
+    T: Send + 'static,
+{
+    type Properties = BufferProperties;
+
+    fn from_properties(
+        name: &str,
+        context: State<Context>,
+        props: Self::Properties,
+    ) -> Result<Self> {
+        if props.capacity == 0 {
+            return Err(anyhow!("A buffer needs a capacity of at least one."));
+        }
+        Ok(Self::new(name, context, props.capacity))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct DebounceProperties {
+    /// How long the input has to be quiet, in milliseconds.
+    pub delay_ms: u64,
+}
+
+/// Sends the latest input element once no other element arrived for `delay`.
+///
+/// Time is taken from [`Context::now`]. The node asks the executor for an update when the delay
+/// is over, see [`Context::request_update_at`].
+#[derive(Connectable)]
+pub struct Debounce<T> {
+    name: String,
+    delay: Duration,
+    latest: Mutex<Option<(T, Instant)>>,
+    context: State<Context>,
+
+    #[input]
+    pub input: Input<T>,
+    #[output]
+    pub output: Output<T>,
+}
+
+impl<T> Debounce<T> {
+    pub fn new(name: &str, context: State<Context>, delay: Duration) -> Self {
+        Self {
+            name: name.into(),
+            delay,
+            latest: Mutex::new(None),
+            context: context.clone(),
+            input: Input::new(),
+            output: Output::new(context),
+        }
+    }
+}
+
+impl<T> Node for Debounce<T>
+where
+    T: Send + 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn update(&self) -> Result<(), UpdateError> {
+        let mut latest = self.latest.lock().unwrap();
+        let now = self.context.0.lock().unwrap().now();
+        while let Ok(elem) = self.input.next_elem() {
+            *latest = Some((elem, now));
+        }
+        let Some((_, received)) = &*latest else {
+            return Ok(());
+        };
+        let due = *received + self.delay;
+        if due <= now {
+            let (elem, _) = latest.take().unwrap();
+            let _ = self.output.clone().send(elem);
+        } else {
+            self.context.0.lock().unwrap().request_update_at(due);
+        }
+        Ok(())
+    }
+}
+
+impl<T> FromProperties for Debounce<T>
+where
+    T: Send + 'static,
+{
+    type Properties = DebounceProperties;
+
+    fn from_properties(
+        name: &str,
+        context: State<Context>,
+        props: Self::Properties,
+    ) -> Result<Self> {
+        Ok(Self::new(
+            name,
+            context,
+            Duration::from_millis(props.delay_ms),
+        ))
+    }
+}
@@ -0,0 +1,329 @@
+use anyhow::{anyhow, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{any::Any, collections::VecDeque, rc::Rc, sync::Mutex};
+
+use flowrs_derive::Connectable;
+
+use crate::{
+    connection::{Input, Output, RuntimeConnectable},
+    node::{Context, FromProperties, Node, State, UpdateError},
+    nodes::{
+        function::{pair, Pending},
+        std::{join::Unmatched, NoProperties},
+    },
+};
+
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct ZipProperties {
+    /// The number of elements buffered per input between updates, unbounded if missing.
+    #[serde(default)]
+    pub capacity: Option<usize>,
+}
+
+/// Pairs the n-th elements of both inputs.
+///
+/// Elements are buffered until an element arrives on the other input, so the inputs may run
+/// ahead of each other. With a capacity, the oldest elements of an input running further ahead
+/// are sent to the optional `unmatched` output.
+///
+/// The capacity bounds the elements buffered between updates. An update first pairs all
+/// elements queued at both inputs and only then evicts, so that no element is evicted while its
+/// partner is still queued.
+#[derive(Connectable)]
+pub struct Zip<A, B> {
+    name: String,
+    capacity: Option<usize>,
+    pending: Pending<A, B>,
+
+    #[input]
+    pub input_1: Input<A>,
+    #[input]
+    pub input_2: Input<B>,
+    #[output]
+    pub output: Output<(A, B)>,
+    #[output(optional)]
+    pub unmatched: Output<Unmatched<A, B>>,
+}
+
+impl<A, B> Zip<A, B> {
+    /// Creates a zip buffering any number of elements.
+    pub fn new(name: &str, context: State<Context>) -> Self {
+        Self::build(name, context, None)
+    }
+
+    /// Creates a zip buffering at most `capacity` elements per input.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is zero.
+    pub fn with_capacity(name: &str, context: State<Context>, capacity: usize) -> Self {
+        assert!(capacity > 0, "A zip needs a capacity of at least one.");
+        Self::build(name, context, Some(capacity))
+    }
+
+    fn build(name: &str, context: State<Context>, capacity: Option<usize>) -> Self {
+        Self {
+            name: name.into(),
+            capacity,
+            pending: Mutex::new((VecDeque::new(), VecDeque::new())),
+            input_1: Input::new(),
+            input_2: Input::new(),
+            output: Output::new(context.clone()),
+            unmatched: Output::new(context),
+        }
+    }
+
+    /// The number of elements waiting for a partner.
+    pub fn len(&self) -> usize {
+        let pending = self.pending.lock().unwrap();
+        pending.0.len() + pending.1.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<A, B> Node for Zip<A, B>
+where
+    A: Send + 'static,
+    B: Send + 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn update(&self) -> Result<(), UpdateError> {
+        for pair in pair(&self.pending, &self.input_1, &self.input_2) {
+            let _ = self.output.clone().send(pair);
+        }
+        let Some(capacity) = self.capacity else {
+            return Ok(());
+        };
+        // After pairing at most one input has elements left.
+        let mut pending = self.pending.lock().unwrap();
+        let (lhs, rhs) = &mut *pending;
+        let excess = lhs.len().saturating_sub(capacity);
+        for elem in lhs.drain(..excess) {
+            let _ = self.unmatched.clone().send(Unmatched::Input1(elem));
+        }
+        let excess = rhs.len().saturating_sub(capacity);
+        for elem in rhs.drain(..excess) {
+            let _ = self.unmatched.clone().send(Unmatched::Input2(elem));
+        }
+        Ok(())
+    }
+}
+
+impl<A, B> FromProperties for Zip<A, B>
+where
+    A: Send + 'static,
+    B: Send + 'static,
+{
+    type Properties = ZipProperties;
+
+    fn from_properties(
+        name: &str,
+        context: State<Context>,
+        props: Self::Properties,
+    ) -> Result<Self> {
+        Ok(match props.capacity {
+            Some(0) => return Err(anyhow!("A zip needs a capacity of at least one.")),
+            Some(capacity) => Self::with_capacity(name, context, capacity),
+            None => Self::new(name, context),
+        })
+    }
+}
+
+/// An operator of an [`Arithmetic`] node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+/// An element type of an [`Arithmetic`] node.
+///
+/// Integer operations fail on overflow instead of panicking or wrapping around, float operations
+/// overflow to infinity.
+pub trait Operand: Sized {
+    fn is_zero(&self) -> bool;
+
+    /// Applies `operator` to `self` and `rhs`, `None` if the result overflows.
+    fn apply(self, operator: Operator, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! integer_operand {
+    ($($t:ty),*) => {$(
+        impl Operand for $t {
+            fn is_zero(&self) -> bool {
+                *self == 0
+            }
+
+            fn apply(self, operator: Operator, rhs: Self) -> Option<Self> {
+                match operator {
+                    Operator::Add => self.checked_add(rhs),
+                    Operator::Sub => self.checked_sub(rhs),
+                    Operator::Mul => self.checked_mul(rhs),
+                    Operator::Div => self.checked_div(rhs),
+                }
+            }
+        }
+    )*};
+}
+
+macro_rules! float_operand {
+    ($($t:ty),*) => {$(
+        impl Operand for $t {
+            fn is_zero(&self) -> bool {
+                *self == 0.0
+            }
+
+            fn apply(self, operator: Operator, rhs: Self) -> Option<Self> {
+                Some(match operator {
+                    Operator::Add => self + rhs,
+                    Operator::Sub => self - rhs,
+                    Operator::Mul => self * rhs,
+                    Operator::Div => self / rhs,
+                })
+            }
+        }
+    )*};
+}
+
+integer_operand!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+float_operand!(f32, f64);
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct ArithmeticProperties {
+    pub operator: Operator,
+}
+
+/// Applies an operator to the n-th elements of both inputs, e.g. `input_1 - input_2`.
+///
+/// Elements are paired like in [`Zip`]. A division by zero or an overflow, see [`Operand`],
+/// fails the update and drops the pair.
+#[derive(Connectable)]
+pub struct Arithmetic<T> {
+    name: String,
+    operator: Operator,
+    pending: Pending<T, T>,
+
+    #[input]
+    pub input_1: Input<T>,
+    #[input]
+    pub input_2: Input<T>,
+    #[output]
+    pub output: Output<T>,
+}
+
+impl<T> Arithmetic<T> {
+    pub fn new(name: &str, context: State<Context>, operator: Operator) -> Self {
+        Self {
+            name: name.into(),
+            operator,
+            pending: Mutex::new((VecDeque::new(), VecDeque::new())),
+            input_1: Input::new(),
+            input_2: Input::new(),
+            output: Output::new(context),
+        }
+    }
+}
+
+impl<T> Node for Arithmetic<T>
+where
+    T: Operand + Send + 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn update(&self) -> Result<(), UpdateError> {
+        let mut res = Ok(());
+        for (lhs, rhs) in pair(&self.pending, &self.input_1, &self.input_2) {
+            if self.operator == Operator::Div && rhs.is_zero() {
+                res = Err(anyhow!("Division by zero in node '{}'.", self.name).into());
+                continue;
+            }
+            match lhs.apply(self.operator, rhs) {
+                Some(elem) => {
+                    let _ = self.output.clone().send(elem);
+                }
+                None => res = Err(anyhow!("Arithmetic overflow in node '{}'.", self.name).into()),
+            }
+        }
+        res
+    }
+}
+
+impl<T> FromProperties for Arithmetic<T>
+where
+    T: Operand + Send + 'static,
+{
+    type Properties = ArithmeticProperties;
+
+    fn from_properties(
+        name: &str,
+        context: State<Context>,
+        props: Self::Properties,
+    ) -> Result<Self> {
+        Ok(Self::new(name, context, props.operator))
+    }
+}
+
+/// Forwards the elements of both inputs to a single output.
+#[derive(Connectable)]
+pub struct Merge<T> {
+    name: String,
+
+    #[input]
+    pub input_1: Input<T>,
+    #[input]
+    pub input_2: Input<T>,
+    #[output]
+    pub output: Output<T>,
+}
+
+impl<T> Merge<T> {
+    pub fn new(name: &str, context: State<Context>) -> Self {
+        Self {
+            name: name.into(),
+            input_1: Input::new(),
+            input_2: Input::new(),
+            output: Output::new(context),
+        }
+    }
+}
+
+impl<T> Node for Merge<T>
+where
+    T: Send + 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn update(&self) -> Result<(), UpdateError> {
+        for input in [&self.input_1, &self.input_2] {
+            while let Ok(elem) = input.next_elem() {
+                let _ = self.output.clone().send(elem);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T> FromProperties for Merge<T>
+where
+    T: Send + 'static,
+{
+    type Properties = NoProperties;
+
+    fn from_properties(name: &str, context: State<Context>, _: Self::Properties) -> Result<Self> {
+        Ok(Self::new(name, context))
+    }
+}
@@ -0,0 +1,304 @@
+use std::{
+    any::Any,
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use flowrs_derive::Connectable;
+
+use crate::{
+    connection::{Input, Output, RuntimeConnectable},
+    node::{Context, Node, State, UpdateError},
+};
+
+type KeyFn<T, K> = Arc<dyn Fn(&T) -> K + Send + Sync>;
+
+/// An element that was dropped by a joining node without a partner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Unmatched<A, B> {
+    Input1(A),
+    Input2(B),
+}
+
+/// When an element arrived, with a sequence number to order elements of the same update.
+type Arrival = (Instant, u64);
+
+/// The buffered elements of one input, by key and in arrival order per key.
+type Waiting<K, T> = HashMap<K, VecDeque<(Arrival, T)>>;
+
+struct Pending<K, A, B> {
+    lhs: Waiting<K, A>,
+    rhs: Waiting<K, B>,
+    received: u64,
+}
+
+/// Removes the oldest element waiting for `key`.
+fn take<K: Eq + Hash, T>(waiting: &mut Waiting<K, T>, key: &K) -> Option<T> {
+    let queue = waiting.get_mut(key)?;
+    let (_, elem) = queue.pop_front()?;
+    if queue.is_empty() {
+        waiting.remove(key);
+    }
+    Some(elem)
+}
+
+/// Removes the elements that arrived at or before `oldest`.
+fn expire<K, T>(waiting: &mut Waiting<K, T>, oldest: Instant) -> Vec<(Arrival, T)> {
+    let mut expired = Vec::new();
+    waiting.retain(|_, queue| {
+        while queue.front().is_some_and(|((at, _), _)| *at <= oldest) {
+            expired.extend(queue.pop_front());
+        }
+        !queue.is_empty()
+    });
+    expired
+}
+
+/// Removes the oldest elements until at most `capacity` elements are left, oldest first.
+fn evict<K: Clone + Eq + Hash, T>(waiting: &mut Waiting<K, T>, capacity: usize) -> Vec<T> {
+    let len: usize = waiting.values().map(VecDeque::len).sum();
+    let mut evicted = Vec::new();
+    for _ in capacity..len {
+        let Some(key) = waiting
+            .iter()
+            .filter_map(|(key, queue)| queue.front().map(|(at, _)| (*at, key)))
+            .min_by_key(|(at, _)| *at)
+            .map(|(_, key)| key.clone())
+        else {
+            break;
+        };
+        evicted.extend(take(waiting, &key));
+    }
+    evicted
+}
+
+fn first_arrival<K, T>(waiting: &Waiting<K, T>) -> Option<Instant> {
+    waiting
+        .values()
+        .filter_map(|queue| queue.front().map(|((at, _), _)| *at))
+        .min()
+}
+
+/// Pairs the elements of both inputs that have the same key.
+///
+/// Elements wait until an element with the same key arrives on the other input, so the inputs
+/// may arrive in any order. Elements with equal keys are paired in arrival order. With a
+/// timeout, elements that waited that long according to [`Context::now`] are sent to the
+/// optional `unmatched` output. With a capacity, so are the oldest elements of an input beyond
+/// it.
+///
+/// The capacity bounds the elements buffered between updates. An update first pairs all
+/// elements queued at both inputs and only then evicts, so that no element is evicted while its
+/// partner is still queued.
+#[derive(Connectable)]
+pub struct KeyedJoin<K, A, B> {
+    name: String,
+    key_1: KeyFn<A, K>,
+    key_2: KeyFn<B, K>,
+    timeout: Option<Duration>,
+    capacity: Option<usize>,
+    pending: Mutex<Pending<K, A, B>>,
+    context: State<Context>,
+
+    #[input]
+    pub input_1: Input<A>,
+    #[input]
+    pub input_2: Input<B>,
+    #[output]
+    pub output: Output<(A, B)>,
+    #[output(optional)]
+    pub unmatched: Output<Unmatched<A, B>>,
+}
+
+impl<K, A, B> KeyedJoin<K, A, B> {
+    /// Creates a join whose elements wait for a partner indefinitely.
+    ///
+    /// Elements whose key never arrives on the other input are buffered forever, see
+    /// [`KeyedJoin::with_bounds`] to bound them.
+    pub fn new<F1, F2>(name: &str, context: State<Context>, key_1: F1, key_2: F2) -> Self
+    where
+        F1: Fn(&A) -> K + Send + Sync + 'static,
+        F2: Fn(&B) -> K + Send + Sync + 'static,
+    {
+        Self::with_bounds(name, context, None, None, key_1, key_2)
+    }
+
+    /// Creates a join buffering at most `capacity` elements per input.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is zero.
+    pub fn with_capacity<F1, F2>(
+        name: &str,
+        context: State<Context>,
+        capacity: usize,
+        key_1: F1,
+        key_2: F2,
+    ) -> Self
+    where
+        F1: Fn(&A) -> K + Send + Sync + 'static,
+        F2: Fn(&B) -> K + Send + Sync + 'static,
+    {
+        Self::with_bounds(name, context, None, Some(capacity), key_1, key_2)
+    }
+
+    /// Creates a join that gives up on elements that waited for `timeout`.
+    pub fn with_timeout<F1, F2>(
+        name: &str,
+        context: State<Context>,
+        timeout: Duration,
+        key_1: F1,
+        key_2: F2,
+    ) -> Self
+    where
+        F1: Fn(&A) -> K + Send + Sync + 'static,
+        F2: Fn(&B) -> K + Send + Sync + 'static,
+    {
+        Self::with_bounds(name, context, Some(timeout), None, key_1, key_2)
+    }
+
+    /// Creates a join that gives up on elements that waited for `timeout`, if any, and buffers
+    /// at most `capacity` elements per input, if any.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is zero.
+    pub fn with_bounds<F1, F2>(
+        name: &str,
+        context: State<Context>,
+        timeout: Option<Duration>,
+        capacity: Option<usize>,
+        key_1: F1,
+        key_2: F2,
+    ) -> Self
+    where
+        F1: Fn(&A) -> K + Send + Sync + 'static,
+        F2: Fn(&B) -> K + Send + Sync + 'static,
+    {
+        assert!(
+            capacity != Some(0),
+            "A join needs a capacity of at least one."
+        );
+        Self {
+            name: name.into(),
+            key_1: Arc::new(key_1),
+            key_2: Arc::new(key_2),
+            timeout,
+            capacity,
+            pending: Mutex::new(Pending {
+                lhs: HashMap::new(),
+                rhs: HashMap::new(),
+                received: 0,
+            }),
+            context: context.clone(),
+            input_1: Input::new(),
+            input_2: Input::new(),
+            output: Output::new(context.clone()),
+            unmatched: Output::new(context),
+        }
+    }
+
+    /// The number of elements waiting for a partner.
+    pub fn len(&self) -> usize {
+        let pending = self.pending.lock().unwrap();
+        pending.lhs.values().map(VecDeque::len).sum::<usize>()
+            + pending.rhs.values().map(VecDeque::len).sum::<usize>()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K, A, B> Node for KeyedJoin<K, A, B>
+where
+    K: Clone + Eq + Hash + Send + 'static,
+    A: Send + 'static,
+    B: Send + 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn update(&self) -> Result<(), UpdateError> {
+        let mut guard = self.pending.lock().unwrap();
+        let pending = &mut *guard;
+        let now = self.context.0.lock().unwrap().now();
+        while let Ok(lhs) = self.input_1.next_elem() {
+            let key = (self.key_1)(&lhs);
+            match take(&mut pending.rhs, &key) {
+                Some(rhs) => {
+                    let _ = self.output.clone().send((lhs, rhs));
+                }
+                None => {
+                    let arrival = (now, pending.received);
+                    pending.received += 1;
+                    pending
+                        .lhs
+                        .entry(key)
+                        .or_default()
+                        .push_back((arrival, lhs));
+                }
+            }
+        }
+        while let Ok(rhs) = self.input_2.next_elem() {
+            let key = (self.key_2)(&rhs);
+            match take(&mut pending.lhs, &key) {
+                Some(lhs) => {
+                    let _ = self.output.clone().send((lhs, rhs));
+                }
+                None => {
+                    let arrival = (now, pending.received);
+                    pending.received += 1;
+                    pending
+                        .rhs
+                        .entry(key)
+                        .or_default()
+                        .push_back((arrival, rhs));
+                }
+            }
+        }
+        if let Some(capacity) = self.capacity {
+            for elem in evict(&mut pending.lhs, capacity) {
+                let _ = self.unmatched.clone().send(Unmatched::Input1(elem));
+            }
+            for elem in evict(&mut pending.rhs, capacity) {
+                let _ = self.unmatched.clone().send(Unmatched::Input2(elem));
+            }
+        }
+
+        let Some(timeout) = self.timeout else {
+            return Ok(());
+        };
+        if let Some(oldest) = now.checked_sub(timeout) {
+            let mut expired: Vec<_> = expire(&mut pending.lhs, oldest)
+                .into_iter()
+                .map(|(at, elem)| (at, Unmatched::Input1(elem)))
+                .chain(
+                    expire(&mut pending.rhs, oldest)
+                        .into_iter()
+                        .map(|(at, elem)| (at, Unmatched::Input2(elem))),
+                )
+                .collect();
+            expired.sort_by_key(|(at, _)| *at);
+            for (_, elem) in expired {
+                let _ = self.unmatched.clone().send(elem);
+            }
+        }
+        let first = first_arrival(&pending.lhs)
+            .into_iter()
+            .chain(first_arrival(&pending.rhs))
+            .min();
+        if let Some(first) = first {
+            self.context
+                .0
+                .lock()
+                .unwrap()
+                .request_update_at(first + timeout);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+//! Generic nodes for common tasks.
+//!
+//! All nodes derive [`Connectable`](flowrs_derive::Connectable), so they can be connected
+//! statically or added to a [`Flow`](crate::flow::Flow). Nodes that need no closure implement
+//! [`FromProperties`](crate::node::FromProperties) for concrete element types, so they can be
+//! registered with
+//! [`NodeRegistry::register_typed_node`](crate::registry::NodeRegistry::register_typed_node).
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+mod buffer;
+mod combine;
+mod join;
+mod route;
+mod sink;
+mod source;
+mod transform;
+mod window;
+
+pub use self::buffer::{Buffer, BufferProperties, Debounce, DebounceProperties};
+pub use self::combine::{
+    Arithmetic, ArithmeticProperties, Merge, Operand, Operator, Zip, ZipProperties,
+};
+pub use self::join::{KeyedJoin, Unmatched};
+pub use self::route::{Broadcast, Split};
+pub use self::sink::{Collect, Log};
+pub use self::source::{Constant, ConstantProperties};
+pub use self::transform::{Counter, CounterProperties, Filter, Map};
+pub use self::window::{
+    Aggregation, Aggregator, Fold, Max, Mean, Min, Numeric, Sum, Window, WindowKind,
+    WindowProperties,
+};
+
+/// The properties of nodes that can not be configured.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct NoProperties {}
@@ -0,0 +1,113 @@
+use anyhow::Result;
+use std::{any::Any, rc::Rc, sync::Arc};
+
+use flowrs_derive::Connectable;
+
+use crate::{
+    connection::{Input, Output, RuntimeConnectable},
+    node::{Context, FromProperties, Node, State},
+    nodes::std::NoProperties,
+};
+
+type PredicateFn<T> = Arc<dyn Fn(&T) -> bool + Send + Sync>;
+
+/// Sends a copy of each input element to both outputs.
+#[derive(Connectable)]
+pub struct Broadcast<T> {
+    name: String,
+
+    #[input]
+    pub input: Input<T>,
+    #[output]
+    pub output_1: Output<T>,
+    #[output]
+    pub output_2: Output<T>,
+}
+
+impl<T> Broadcast<T> {
+    pub fn new(name: &str, context: State<Context>) -> Self {
+        Self {
+            name: name.into(),
+            input: Input::new(),
+            output_1: Output::new(context.clone()),
+            output_2: Output::new(context),
+        }
+    }
+}
+
+#[flowrs::node]
+impl<T> Node for Broadcast<T>
+where
+    T: Clone + Send + 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[on_input(input)]
+    fn handle(&self, elem: T) {
+        let _ = self.output_1.clone().send(elem.clone());
+        let _ = self.output_2.clone().send(elem);
+    }
+}
+
+impl<T> FromProperties for Broadcast<T>
+where
+    T: Clone + Send + 'static,
+{
+    type Properties = NoProperties;
+
+    fn from_properties(name: &str, context: State<Context>, _: Self::Properties) -> Result<Self> {
+        Ok(Self::new(name, context))
+    }
+}
+
+/// Sends the input elements a predicate holds for to `matched`, all others to `unmatched`.
+///
+/// Both outputs are optional, so they may stay unconnected and their elements are dropped.
+#[derive(Connectable)]
+pub struct Split<T> {
+    name: String,
+    predicate: PredicateFn<T>,
+
+    #[input]
+    pub input: Input<T>,
+    #[output(optional)]
+    pub matched: Output<T>,
+    #[output(optional)]
+    pub unmatched: Output<T>,
+}
+
+impl<T> Split<T> {
+    pub fn new<P>(name: &str, context: State<Context>, predicate: P) -> Self
+    where
+        P: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            name: name.into(),
+            predicate: Arc::new(predicate),
+            input: Input::new(),
+            matched: Output::new(context.clone()),
+            unmatched: Output::new(context),
+        }
+    }
+}
+
+#[flowrs::node]
+impl<T> Node for Split<T>
+where
+    T: Send + 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[on_input(input)]
+    fn handle(&self, elem: T) {
+        let output = match (self.predicate)(&elem) {
+            true => &self.matched,
+            false => &self.unmatched,
+        };
+        let _ = output.clone().send(elem);
+    }
+}
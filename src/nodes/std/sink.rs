@@ -0,0 +1,130 @@
+use anyhow::Result;
+use std::{
+    any::Any,
+    fmt::Debug,
+    io::{self, Write},
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+use flowrs_derive::Connectable;
+
+use crate::{
+    connection::{Input, RuntimeConnectable},
+    node::{Context, FromProperties, Node, State, UpdateError},
+    nodes::std::NoProperties,
+};
+
+/// Collects all input elements.
+#[derive(Connectable)]
+pub struct Collect<T> {
+    name: String,
+    values: Arc<Mutex<Vec<T>>>,
+
+    #[input]
+    pub input: Input<T>,
+}
+
+impl<T> Collect<T> {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            values: Arc::new(Mutex::new(Vec::new())),
+            input: Input::new(),
+        }
+    }
+
+    /// The collected elements, shared with the node. Keep a clone before adding the node to a
+    /// flow to inspect them later.
+    pub fn values(&self) -> Arc<Mutex<Vec<T>>> {
+        self.values.clone()
+    }
+}
+
+impl<T> Node for Collect<T>
+where
+    T: Send + 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn update(&self) -> Result<(), UpdateError> {
+        let mut values = self.values.lock().unwrap();
+        while let Ok(elem) = self.input.next_elem() {
+            values.push(elem);
+        }
+        Ok(())
+    }
+}
+
+impl<T> FromProperties for Collect<T>
+where
+    T: Send + 'static,
+{
+    type Properties = NoProperties;
+
+    fn from_properties(name: &str, _: State<Context>, _: Self::Properties) -> Result<Self> {
+        Ok(Self::new(name))
+    }
+}
+
+/// Writes each input element as a line, prefixed with the node name.
+///
+/// Lines go to stdout unless the node is created with [`Log::with_writer`]. A failed write fails
+/// the update.
+#[derive(Connectable)]
+pub struct Log<T> {
+    name: String,
+    writer: Mutex<Box<dyn Write + Send>>,
+
+    #[input]
+    pub input: Input<T>,
+}
+
+impl<T> Log<T> {
+    pub fn new(name: &str) -> Self {
+        Self::with_writer(name, io::stdout())
+    }
+
+    /// Creates a log writing its lines to `writer`.
+    pub fn with_writer<W>(name: &str, writer: W) -> Self
+    where
+        W: Write + Send + 'static,
+    {
+        Self {
+            name: name.into(),
+            writer: Mutex::new(Box::new(writer)),
+            input: Input::new(),
+        }
+    }
+}
+
+#[flowrs::node]
+impl<T> Node for Log<T>
+where
+    T: Debug + Send + 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[on_input(input)]
+    fn handle(&self, elem: T) -> Result<(), UpdateError> {
+        let mut writer = self.writer.lock().unwrap();
+        writeln!(writer, "{}: {:?}", self.name, elem)
+            .and_then(|_| writer.flush())
+            .map_err(|err| UpdateError::Other(err.into()))
+    }
+}
+
+impl<T> FromProperties for Log<T>
+where
+    T: Debug + Send + 'static,
+{
+    type Properties = NoProperties;
+
+    fn from_properties(name: &str, _: State<Context>, _: Self::Properties) -> Result<Self> {
+        Ok(Self::new(name))
+    }
+}
@@ -0,0 +1,71 @@
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{any::Any, rc::Rc};
+
+use flowrs_derive::Connectable;
+
+use crate::{
+    connection::{Output, RuntimeConnectable},
+    node::{Context, FromProperties, Node, State, UpdateError},
+};
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct ConstantProperties<T> {
+    pub value: T,
+}
+
+/// Sends its value once, in its first update.
+#[derive(Connectable)]
+pub struct Constant<T> {
+    name: String,
+    value: T,
+    sent: State<bool>,
+
+    #[output]
+    pub output: Output<T>,
+}
+
+impl<T> Constant<T> {
+    pub fn new(name: &str, context: State<Context>, value: T) -> Self {
+        Self {
+            name: name.into(),
+            value,
+            sent: State::new(false),
+            output: Output::new(context),
+        }
+    }
+}
+
+impl<T> Node for Constant<T>
+where
+    T: Clone + Send + 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn update(&self) -> Result<(), UpdateError> {
+        let mut sent = self.sent.0.lock().unwrap();
+        if !*sent {
+            *sent = true;
+            let _ = self.output.clone().send(self.value.clone());
+        }
+        Ok(())
+    }
+}
+
+impl<T> FromProperties for Constant<T>
+where
+    T: DeserializeOwned + JsonSchema + Clone + Send + 'static,
+{
+    type Properties = ConstantProperties<T>;
+
+    fn from_properties(
+        name: &str,
+        context: State<Context>,
+        props: Self::Properties,
+    ) -> Result<Self> {
+        Ok(Self::new(name, context, props.value))
+    }
+}
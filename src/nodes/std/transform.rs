@@ -0,0 +1,147 @@
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{any::Any, rc::Rc, sync::Arc};
+
+use flowrs_derive::Connectable;
+
+use crate::{
+    connection::{Input, Output, RuntimeConnectable},
+    function::FnNode,
+    node::{Context, FromProperties, Node, State, StateError},
+};
+
+type PredicateFn<T> = Arc<dyn Fn(&T) -> bool + Send + Sync>;
+
+/// Sends the result of a function applied to each input element.
+pub type Map<I, O> = FnNode<I, O>;
+
+/// Forwards the input elements a predicate holds for and drops the others.
+#[derive(Connectable)]
+pub struct Filter<T> {
+    name: String,
+    predicate: PredicateFn<T>,
+
+    #[input]
+    pub input: Input<T>,
+    #[output]
+    pub output: Output<T>,
+}
+
+impl<T> Filter<T> {
+    pub fn new<P>(name: &str, context: State<Context>, predicate: P) -> Self
+    where
+        P: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            name: name.into(),
+            predicate: Arc::new(predicate),
+            input: Input::new(),
+            output: Output::new(context),
+        }
+    }
+}
+
+#[flowrs::node]
+impl<T> Node for Filter<T>
+where
+    T: Send + 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[on_input(input)]
+    fn handle(&self, elem: T) {
+        if (self.predicate)(&elem) {
+            let _ = self.output.clone().send(elem);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct CounterProperties {
+    /// The count before the first element.
+    #[serde(default)]
+    pub start: u64,
+}
+
+/// Counts its input elements and sends the count after each one.
+#[derive(Connectable)]
+pub struct Counter<T> {
+    name: String,
+    count: State<u64>,
+
+    #[input]
+    pub input: Input<T>,
+    #[output]
+    pub output: Output<u64>,
+}
+
+impl<T> Counter<T> {
+    pub fn new(name: &str, context: State<Context>) -> Self {
+        Self::starting_at(name, context, 0)
+    }
+
+    pub fn starting_at(name: &str, context: State<Context>, start: u64) -> Self {
+        Self {
+            name: name.into(),
+            count: State::new(start),
+            input: Input::new(),
+            output: Output::new(context),
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        *self.count.0.lock().unwrap()
+    }
+}
+
+#[flowrs::node]
+impl<T> Node for Counter<T>
+where
+    T: Send + 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[on_input(input)]
+    fn handle(&self, _: T) {
+        let count = {
+            let mut count = self.count.0.lock().unwrap();
+            *count += 1;
+            *count
+        };
+        let _ = self.output.clone().send(count);
+    }
+
+    fn export_state(&self) -> Result<Option<Value>, StateError> {
+        Ok(Some(json!({ "count": self.count() })))
+    }
+
+    fn import_state(&self, state: Value) -> Result<(), StateError> {
+        let count = state["count"].as_u64().ok_or(StateError::Incompatible {
+            node: self.name.clone(),
+            message: "Missing count.".into(),
+        })?;
+        *self.count.0.lock().unwrap() = count;
+        Ok(())
+    }
+}
+
+impl<T> FromProperties for Counter<T>
+where
+    T: Send + 'static,
+{
+    type Properties = CounterProperties;
+
+    fn from_properties(
+        name: &str,
+        context: State<Context>,
+        props: Self::Properties,
+    ) -> Result<Self> {
+        Ok(Self::starting_at(name, context, props.start))
+    }
+}
@@ -0,0 +1,466 @@
+use anyhow::{anyhow, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{
+    any::Any,
+    collections::VecDeque,
+    iter::Sum as IterSum,
+    marker::PhantomData,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use flowrs_derive::Connectable;
+
+use crate::{
+    connection::{Input, Output, RuntimeConnectable},
+    node::{Context, FromProperties, Node, State, UpdateError},
+};
+
+/// Combines the elements of a window into a single value.
+///
+/// Windows passed to an aggregator are never empty.
+pub trait Aggregator<T>: Send + Sync + 'static {
+    type Output;
+
+    fn aggregate(&self, window: &[T]) -> Self::Output;
+}
+
+/// The sum of a window.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sum;
+
+impl<T> Aggregator<T> for Sum
+where
+    T: Clone + IterSum<T>,
+{
+    type Output = T;
+
+    fn aggregate(&self, window: &[T]) -> T {
+        window.iter().cloned().sum()
+    }
+}
+
+/// The smallest element of a window, the first one if several are equal.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Min;
+
+impl<T> Aggregator<T> for Min
+where
+    T: Clone + PartialOrd,
+{
+    type Output = T;
+
+    fn aggregate(&self, window: &[T]) -> T {
+        window
+            .iter()
+            .fold(&window[0], |min, elem| if elem < min { elem } else { min })
+            .clone()
+    }
+}
+
+/// The largest element of a window, the first one if several are equal.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Max;
+
+impl<T> Aggregator<T> for Max
+where
+    T: Clone + PartialOrd,
+{
+    type Output = T;
+
+    fn aggregate(&self, window: &[T]) -> T {
+        window
+            .iter()
+            .fold(&window[0], |max, elem| if elem > max { elem } else { max })
+            .clone()
+    }
+}
+
+/// An element type of a [`Mean`].
+///
+/// Implemented for all primitive numbers. Conversions follow `as`, so large 64 and 128 bit
+/// integers lose precision.
+pub trait Numeric {
+    fn to_f64(&self) -> f64;
+
+    /// Converts a mean back to the element type, rounding integers to the nearest value and
+    /// saturating at their bounds.
+    fn from_f64(value: f64) -> Self;
+}
+
+macro_rules! integer_numeric {
+    ($($t:ty),*) => {$(
+        impl Numeric for $t {
+            fn to_f64(&self) -> f64 {
+                *self as f64
+            }
+
+            fn from_f64(value: f64) -> Self {
+                value.round() as $t
+            }
+        }
+    )*};
+}
+
+macro_rules! float_numeric {
+    ($($t:ty),*) => {$(
+        impl Numeric for $t {
+            fn to_f64(&self) -> f64 {
+                *self as f64
+            }
+
+            fn from_f64(value: f64) -> Self {
+                value as $t
+            }
+        }
+    )*};
+}
+
+integer_numeric!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+float_numeric!(f32, f64);
+
+/// The arithmetic mean of a window.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Mean;
+
+impl<T> Aggregator<T> for Mean
+where
+    T: Numeric,
+{
+    type Output = f64;
+
+    fn aggregate(&self, window: &[T]) -> f64 {
+        let sum: f64 = window.iter().map(Numeric::to_f64).sum();
+        sum / window.len() as f64
+    }
+}
+
+/// The [`Mean`] of a window converted back to the element type, see [`Numeric::from_f64`].
+struct ElementMean;
+
+impl<T> Aggregator<T> for ElementMean
+where
+    T: Numeric,
+{
+    type Output = T;
+
+    fn aggregate(&self, window: &[T]) -> T {
+        T::from_f64(Mean.aggregate(window))
+    }
+}
+
+/// Folds a window into an accumulator, starting from a clone of `init`.
+pub struct Fold<T, A, F> {
+    init: A,
+    f: F,
+    _elem: PhantomData<fn(&T)>,
+}
+
+impl<T, A, F> Fold<T, A, F>
+where
+    F: Fn(A, &T) -> A,
+{
+    pub fn new(init: A, f: F) -> Self {
+        Self {
+            init,
+            f,
+            _elem: PhantomData,
+        }
+    }
+}
+
+impl<T, A, F> Aggregator<T> for Fold<T, A, F>
+where
+    T: 'static,
+    A: Clone + Send + Sync + 'static,
+    F: Fn(A, &T) -> A + Send + Sync + 'static,
+{
+    type Output = A;
+
+    fn aggregate(&self, window: &[T]) -> A {
+        window.iter().fold(self.init.clone(), &self.f)
+    }
+}
+
+/// How a [`Window`] groups its input elements.
+///
+/// Time based windows use the time an element was received by the node, taken from
+/// [`Context::now`]. They close in the first update after their end, which the node requests
+/// with [`Context::request_update_at`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WindowKind {
+    /// Consecutive windows of `size` elements.
+    TumblingCount { size: usize },
+    /// Consecutive windows of `size_ms`, the first one starting with the first element. Later
+    /// windows start where the previous one ended. Empty windows are skipped.
+    TumblingTime {
+        #[serde(rename = "size_ms", with = "millis")]
+        #[schemars(rename = "size_ms", with = "u64")]
+        size: Duration,
+    },
+    /// Every `slide` elements, the last `size` elements once `size` elements were received.
+    SlidingCount { size: usize, slide: usize },
+    /// Every `slide_ms`, the elements of the last `size_ms`. Sliding stops while no element is
+    /// in the window and restarts with the next one.
+    SlidingTime {
+        #[serde(rename = "size_ms", with = "millis")]
+        #[schemars(rename = "size_ms", with = "u64")]
+        size: Duration,
+        #[serde(rename = "slide_ms", with = "millis")]
+        #[schemars(rename = "slide_ms", with = "u64")]
+        slide: Duration,
+    },
+    /// Elements that arrived less than `gap_ms` apart.
+    Session {
+        #[serde(rename = "gap_ms", with = "millis")]
+        #[schemars(rename = "gap_ms", with = "u64")]
+        gap: Duration,
+    },
+}
+
+impl WindowKind {
+    fn is_valid(&self) -> bool {
+        match *self {
+            WindowKind::TumblingCount { size } => size > 0,
+            WindowKind::TumblingTime { size } => !size.is_zero(),
+            WindowKind::SlidingCount { size, slide } => size > 0 && slide > 0,
+            WindowKind::SlidingTime { size, slide } => !size.is_zero() && !slide.is_zero(),
+            WindowKind::Session { gap } => !gap.is_zero(),
+        }
+    }
+}
+
+mod millis {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_millis() as u64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_millis)
+    }
+}
+
+/// The elements of open windows.
+struct Pending<T> {
+    elements: VecDeque<(Instant, T)>,
+    /// Elements received since the last sliding count window.
+    received: usize,
+    /// When the current time based window closes.
+    close: Option<Instant>,
+}
+
+/// Aggregates the elements of windows over its input, see [`WindowKind`].
+#[derive(Connectable)]
+pub struct Window<T, O> {
+    name: String,
+    kind: WindowKind,
+    aggregator: Arc<dyn Aggregator<T, Output = O>>,
+    pending: Mutex<Pending<T>>,
+    context: State<Context>,
+
+    #[input]
+    pub input: Input<T>,
+    #[output]
+    pub output: Output<O>,
+}
+
+impl<T, O> Window<T, O> {
+    /// Creates a window node aggregating its windows with `aggregator`.
+    ///
+    /// # Panics
+    ///
+    /// If a size, slide or gap of `kind` is zero.
+    pub fn new<A>(name: &str, context: State<Context>, kind: WindowKind, aggregator: A) -> Self
+    where
+        A: Aggregator<T, Output = O>,
+    {
+        assert!(
+            kind.is_valid(),
+            "Window sizes, slides and gaps must not be zero."
+        );
+        Self {
+            name: name.into(),
+            kind,
+            aggregator: Arc::new(aggregator),
+            pending: Mutex::new(Pending {
+                elements: VecDeque::new(),
+                received: 0,
+                close: None,
+            }),
+            context: context.clone(),
+            input: Input::new(),
+            output: Output::new(context),
+        }
+    }
+}
+
+impl<T, O> Window<T, O>
+where
+    T: Clone + 'static,
+    O: 'static,
+{
+    fn emit<'a, I>(&self, window: I)
+    where
+        I: Iterator<Item = &'a (Instant, T)>,
+        T: 'a,
+    {
+        let window: Vec<T> = window.map(|(_, elem)| elem.clone()).collect();
+        if !window.is_empty() {
+            let _ = self.output.clone().send(self.aggregator.aggregate(&window));
+        }
+    }
+
+    /// Closes the time based windows that ended before `now`.
+    fn close_due(&self, pending: &mut Pending<T>, now: Instant) {
+        match self.kind {
+            WindowKind::TumblingTime { size } => {
+                if let Some(close) = pending.close.filter(|close| *close <= now) {
+                    self.emit(pending.elements.iter());
+                    pending.elements.clear();
+                    // The next window starts where this one ended, skipping empty windows.
+                    let windows = (now - close).as_nanos() / size.as_nanos() + 1;
+                    pending.close =
+                        Some(close + Duration::from_nanos((windows * size.as_nanos()) as u64));
+                }
+            }
+            WindowKind::SlidingTime { size, slide } => {
+                while let Some(close) = pending.close.filter(|close| *close <= now) {
+                    self.emit(
+                        pending
+                            .elements
+                            .iter()
+                            .filter(|(at, _)| *at + size >= close && *at < close),
+                    );
+                    pending.elements.retain(|(at, _)| *at + size > close);
+                    pending.close = match pending.elements.is_empty() {
+                        true => None,
+                        false => Some(close + slide),
+                    };
+                }
+            }
+            WindowKind::Session { gap } => {
+                if pending
+                    .elements
+                    .back()
+                    .is_some_and(|(at, _)| *at + gap <= now)
+                {
+                    self.emit(pending.elements.iter());
+                    pending.elements.clear();
+                }
+            }
+            WindowKind::TumblingCount { .. } | WindowKind::SlidingCount { .. } => {}
+        }
+    }
+
+    fn push(&self, pending: &mut Pending<T>, now: Instant, elem: T) {
+        pending.elements.push_back((now, elem));
+        match self.kind {
+            WindowKind::TumblingCount { size } => {
+                if pending.elements.len() == size {
+                    self.emit(pending.elements.iter());
+                    pending.elements.clear();
+                }
+            }
+            WindowKind::SlidingCount { size, slide } => {
+                pending.received += 1;
+                if pending.elements.len() > size {
+                    pending.elements.pop_front();
+                }
+                if pending.elements.len() == size && pending.received >= slide {
+                    self.emit(pending.elements.iter());
+                    pending.received = 0;
+                }
+            }
+            WindowKind::TumblingTime { size } => {
+                pending.close.get_or_insert(now + size);
+            }
+            WindowKind::SlidingTime { slide, .. } => {
+                pending.close.get_or_insert(now + slide);
+            }
+            WindowKind::Session { .. } => {}
+        }
+    }
+
+    /// When the next window closes, if it depends on time.
+    fn deadline(&self, pending: &Pending<T>) -> Option<Instant> {
+        match self.kind {
+            // Tumbling windows keep their boundaries while empty, but need no update then.
+            WindowKind::TumblingTime { .. } => {
+                pending.close.filter(|_| !pending.elements.is_empty())
+            }
+            WindowKind::SlidingTime { .. } => pending.close,
+            WindowKind::Session { gap } => pending.elements.back().map(|(at, _)| *at + gap),
+            WindowKind::TumblingCount { .. } | WindowKind::SlidingCount { .. } => None,
+        }
+    }
+}
+
+impl<T, O> Node for Window<T, O>
+where
+    T: Clone + Send + 'static,
+    O: Send + 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn update(&self) -> Result<(), UpdateError> {
+        let mut pending = self.pending.lock().unwrap();
+        let now = self.context.0.lock().unwrap().now();
+        self.close_due(&mut pending, now);
+        while let Ok(elem) = self.input.next_elem() {
+            self.push(&mut pending, now, elem);
+        }
+        if let Some(deadline) = self.deadline(&pending) {
+            self.context.0.lock().unwrap().request_update_at(deadline);
+        }
+        Ok(())
+    }
+}
+
+/// A built-in aggregator, see [`WindowProperties`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Aggregation {
+    Sum,
+    Min,
+    Max,
+    /// The [`Mean`] in the element type, rounded for integers.
+    Mean,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct WindowProperties {
+    pub window: WindowKind,
+    pub aggregate: Aggregation,
+}
+
+impl<T> FromProperties for Window<T, T>
+where
+    T: Clone + PartialOrd + IterSum<T> + Numeric + Send + 'static,
+{
+    type Properties = WindowProperties;
+
+    fn from_properties(
+        name: &str,
+        context: State<Context>,
+        props: Self::Properties,
+    ) -> Result<Self> {
+        if !props.window.is_valid() {
+            return Err(anyhow!("Window sizes, slides and gaps must not be zero."));
+        }
+        let kind = props.window;
+        Ok(match props.aggregate {
+            Aggregation::Sum => Self::new(name, context, kind, Sum),
+            Aggregation::Min => Self::new(name, context, kind, Min),
+            Aggregation::Max => Self::new(name, context, kind, Max),
+            Aggregation::Mean => Self::new(name, context, kind, ElementMean),
+        })
+    }
+}
@@ -0,0 +1,228 @@
+use anyhow::Result;
+use serde_json::Value;
+use std::{any::Any, rc::Rc};
+
+use crate::{
+    connection::{AnyInput, AnyOutput, RuntimeConnectable},
+    flow::{Flow, FlowError, NodeId, PortKind},
+    node::{
+        Context, InitError, Node, ReadyError, ShutdownError, State, StateError, UpdateError,
+    },
+    sched::{
+        checkpoint::Checkpoint,
+        definition::{node_id, FlowDefinition, PortDefinition},
+        registry::NodeRegistry,
+    },
+};
+
+/// A port of an inner node that a [`SubflowNode`] exposes as its own port.
+#[derive(Debug, Clone)]
+pub struct ExposedPort {
+    pub name: String,
+    pub node: NodeId,
+    pub index: usize,
+}
+
+/// A node wrapping a whole inner [`Flow`].
+///
+/// Selected inner inputs and outputs are exposed as ports of the subflow. They are the very same
+/// channels as the inner ports, so connecting to a subflow wires the inner node directly. An
+/// update of the subflow updates all inner nodes once.
+pub struct SubflowNode {
+    name: String,
+    flow: Flow,
+    inputs: Vec<ExposedPort>,
+    outputs: Vec<ExposedPort>,
+}
+
+impl SubflowNode {
+    pub fn new(
+        name: &str,
+        flow: Flow,
+        inputs: Vec<ExposedPort>,
+        outputs: Vec<ExposedPort>,
+    ) -> Result<Self, FlowError> {
+        for (ports, kind) in [(&inputs, PortKind::Input), (&outputs, PortKind::Output)] {
+            for port in ports {
+                let handle = flow
+                    .handle(port.node)
+                    .ok_or(FlowError::UnknownNode(port.node))?;
+                let inner = handle.ports();
+                let inner = inner.lock().unwrap();
+                let len = match kind {
+                    PortKind::Input => inner.input_len(),
+                    PortKind::Output => inner.output_len(),
+                };
+                if port.index >= len {
+                    return Err(FlowError::UnknownPort {
+                        node: handle.name().into(),
+                        kind,
+                        index: port.index,
+                    });
+                }
+            }
+        }
+
+        Ok(Self {
+            name: name.into(),
+            flow,
+            inputs,
+            outputs,
+        })
+    }
+
+    /// Builds the inner flow from `definition` and exposes the ports listed in its `inputs` and
+    /// `outputs`.
+    pub fn from_definition(
+        name: &str,
+        definition: &FlowDefinition,
+        registry: &NodeRegistry,
+        context: State<Context>,
+    ) -> Result<Self> {
+        // The exposed ports refer to the nodes of the migrated definition.
+        let definition = &definition.migrated(registry)?;
+        let flow = definition.build(registry, context)?;
+        let expose = |ports: &[PortDefinition]| {
+            ports
+                .iter()
+                .map(|p| {
+                    Ok(ExposedPort {
+                        name: p.name.clone(),
+                        node: node_id(&flow, &p.node)?,
+                        index: p.port,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()
+        };
+        let inputs = expose(&definition.inputs)?;
+        let outputs = expose(&definition.outputs)?;
+        Ok(Self::new(name, flow, inputs, outputs)?)
+    }
+
+    pub fn flow(&self) -> &Flow {
+        &self.flow
+    }
+
+    fn exposed<'a>(
+        &self,
+        ports: &'a [ExposedPort],
+        kind: PortKind,
+        index: usize,
+    ) -> &'a ExposedPort {
+        ports.get(index).unwrap_or_else(|| {
+            panic!(
+                "Index {} out of bounds for SubflowNode with {} len {}.",
+                index,
+                kind,
+                ports.len()
+            )
+        })
+    }
+
+    fn with_inner<R>(&self, port: &ExposedPort, f: impl FnOnce(&dyn RuntimeConnectable) -> R) -> R {
+        let inner = self
+            .flow
+            .handle(port.node)
+            .expect("Exposed nodes are never removed from a subflow.")
+            .ports();
+        let inner = inner.lock().unwrap();
+        f(&*inner)
+    }
+}
+
+impl Node for SubflowNode {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn on_init(&self) -> Result<(), InitError> {
+        let report = self.flow.validate();
+        if report.has_errors() {
+            return Err(InitError::Other(FlowError::Invalid(report).into()));
+        }
+        Ok(self.flow.init_all()?)
+    }
+
+    fn on_ready(&self) -> Result<(), ReadyError> {
+        Ok(self.flow.ready_all()?)
+    }
+
+    fn on_shutdown(&self) -> Result<(), ShutdownError> {
+        Ok(self.flow.shutdown_all()?)
+    }
+
+    /// Updates every inner node, even if one of them fails. The first error is returned.
+    fn update(&self) -> Result<(), UpdateError> {
+        let mut res = Ok(());
+        for handle in self.flow.iter() {
+            if let Err(err) = handle.update() {
+                if res.is_ok() {
+                    res = Err(UpdateError::Other(err));
+                }
+            }
+        }
+        res
+    }
+
+    /// Exports a [`Checkpoint`] of the inner flow.
+    fn export_state(&self) -> Result<Option<Value>, StateError> {
+        let checkpoint = self.flow.checkpoint()?;
+        if checkpoint.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::to_value(checkpoint).map_err(anyhow::Error::from)?))
+    }
+
+    fn import_state(&self, state: Value) -> Result<(), StateError> {
+        let checkpoint: Checkpoint = serde_json::from_value(state).map_err(|err| {
+            StateError::Incompatible {
+                node: self.name.clone(),
+                message: err.to_string(),
+            }
+        })?;
+        Ok(self.flow.restore(&checkpoint)?)
+    }
+}
+
+impl RuntimeConnectable for SubflowNode {
+    fn input_at(&self, index: usize) -> Rc<dyn Any> {
+        let port = self.exposed(&self.inputs, PortKind::Input, index);
+        self.with_inner(port, |inner| inner.input_at(port.index))
+    }
+
+    fn output_at(&self, index: usize) -> Rc<dyn Any> {
+        let port = self.exposed(&self.outputs, PortKind::Output, index);
+        self.with_inner(port, |inner| inner.output_at(port.index))
+    }
+
+    fn dyn_input_at(&self, index: usize) -> Rc<dyn AnyInput> {
+        let port = self.exposed(&self.inputs, PortKind::Input, index);
+        self.with_inner(port, |inner| inner.dyn_input_at(port.index))
+    }
+
+    fn dyn_output_at(&self, index: usize) -> Rc<dyn AnyOutput> {
+        let port = self.exposed(&self.outputs, PortKind::Output, index);
+        self.with_inner(port, |inner| inner.dyn_output_at(port.index))
+    }
+
+    fn output_is_optional(&self, index: usize) -> bool {
+        let port = self.exposed(&self.outputs, PortKind::Output, index);
+        self.with_inner(port, |inner| inner.output_is_optional(port.index))
+    }
+
+    fn input_name_at(&self, index: usize) -> &str {
+        &self.exposed(&self.inputs, PortKind::Input, index).name
+    }
+
+    fn output_name_at(&self, index: usize) -> &str {
+        &self.exposed(&self.outputs, PortKind::Output, index).name
+    }
+
+    fn input_len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    fn output_len(&self) -> usize {
+        self.outputs.len()
+    }
+}
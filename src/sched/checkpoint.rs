@@ -0,0 +1,222 @@
+use anyhow::{Context as AnyhowContext, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+use crate::{
+    node::{Context, State},
+    sched::{
+        definition::{node_id, FlowDefinition},
+        flow::{Flow, FlowError, Port},
+        registry::NodeRegistry,
+        version::Version,
+    },
+};
+
+#[derive(Error, Debug)]
+pub enum CheckpointError {
+    #[error("The checkpoint belongs to flow '{found}', not to flow '{expected}'.")]
+    FlowMismatch { expected: String, found: String },
+
+    #[error("The checkpoint was taken at version {found}, which is incompatible with version {expected} of the flow.")]
+    VersionMismatch { expected: Version, found: Version },
+
+    #[error("The input at index {index} of node '{node}' has queued elements that can not be serialized.")]
+    NotSerializable { node: String, index: usize },
+
+    #[error("The input at index {index} of node '{node}' is not connected.")]
+    UnknownInput { node: String, index: usize },
+}
+
+/// A serializable snapshot of the state of a [`Flow`].
+///
+/// It contains the state exported by each node and the elements queued on every input. Queued
+/// elements are encoded by a serializable edge into their input, see
+/// [`Flow::connect_serializable`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub flow: String,
+    pub version: Version,
+    /// The exported state by node name. Nodes without state are left out.
+    #[serde(default)]
+    pub nodes: BTreeMap<String, Value>,
+    /// Non-empty input queues.
+    #[serde(default)]
+    pub queues: Vec<QueueSnapshot>,
+}
+
+/// The elements queued at an input when a [`Checkpoint`] was taken, oldest first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueueSnapshot {
+    pub node: String,
+    pub port: usize,
+    pub elements: Vec<Value>,
+}
+
+impl Checkpoint {
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).context("Unable to parse checkpoint.")
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("Unable to serialize checkpoint.")
+    }
+
+    /// Returns whether neither node state nor queued elements were captured.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.queues.is_empty()
+    }
+}
+
+impl Flow {
+    /// Captures the state of all nodes and the elements queued on all inputs.
+    ///
+    /// Fails if an input has queued elements but no serializable edge into it. Queues are briefly
+    /// drained to encode their elements, so no node of the flow may be updated concurrently, see
+    /// [`FlowEditor::checkpoint`](crate::editor::FlowEditor::checkpoint) for running flows.
+    pub fn checkpoint(&self) -> Result<Checkpoint> {
+        let mut nodes = BTreeMap::new();
+        for handle in self.iter() {
+            let state = handle
+                .node()
+                .lock()
+                .unwrap()
+                .export_state()
+                .with_context(|| format!("Unable to export state of node '{}'.", handle.name()))?;
+            if let Some(state) = state {
+                nodes.insert(handle.name().to_string(), state);
+            }
+        }
+
+        // Every input is captured, also those fed from outside the flow. Their elements are
+        // encoded with the codec of a serializable edge into them, if there is one.
+        let mut queues = Vec::new();
+        for handle in self.iter() {
+            let ports = handle.ports();
+            let ports = ports.lock().unwrap();
+            for index in 0..ports.input_len() {
+                let to = Port {
+                    node: handle.id(),
+                    index,
+                };
+                let snapshot = self
+                    .edges()
+                    .iter()
+                    .filter(|edge| edge.to() == to)
+                    .find_map(|edge| edge.snapshot());
+                match snapshot {
+                    Some(elements) => {
+                        let elements = elements.with_context(|| {
+                            format!(
+                                "Unable to serialize elements queued at input {} of node '{}'.",
+                                index,
+                                handle.name()
+                            )
+                        })?;
+                        if !elements.is_empty() {
+                            queues.push(QueueSnapshot {
+                                node: handle.name().to_string(),
+                                port: index,
+                                elements,
+                            });
+                        }
+                    }
+                    None if !ports.dyn_input_at(index).is_empty() => {
+                        return Err(CheckpointError::NotSerializable {
+                            node: handle.name().to_string(),
+                            index,
+                        }
+                        .into());
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        Ok(Checkpoint {
+            flow: self.name().to_string(),
+            version: self.version().clone(),
+            nodes,
+            queues,
+        })
+    }
+
+    /// Imports the node state of `checkpoint` and appends its queued elements to the inputs.
+    ///
+    /// The flow is expected to be freshly built from the same definition the checkpoint was taken
+    /// from, or from a compatible version of it, see [`FlowDefinition::restore`].
+    pub fn restore(&self, checkpoint: &Checkpoint) -> Result<()> {
+        if checkpoint.flow != self.name() {
+            return Err(CheckpointError::FlowMismatch {
+                expected: self.name().to_string(),
+                found: checkpoint.flow.clone(),
+            }
+            .into());
+        }
+        if !self.version().is_compatible_with(&checkpoint.version) {
+            return Err(CheckpointError::VersionMismatch {
+                expected: self.version().clone(),
+                found: checkpoint.version.clone(),
+            }
+            .into());
+        }
+
+        for (name, state) in &checkpoint.nodes {
+            let id = node_id(self, name)?;
+            self.node(id)
+                .ok_or(FlowError::UnknownNode(id))?
+                .lock()
+                .unwrap()
+                .import_state(state.clone())
+                .with_context(|| format!("Unable to import state of node '{}'.", name))?;
+        }
+
+        for queue in &checkpoint.queues {
+            let to = Port {
+                node: node_id(self, &queue.node)?,
+                index: queue.port,
+            };
+            let mut edges = self
+                .edges()
+                .iter()
+                .filter(|edge| edge.to() == to)
+                .peekable();
+            if edges.peek().is_none() {
+                return Err(CheckpointError::UnknownInput {
+                    node: queue.node.clone(),
+                    index: queue.port,
+                }
+                .into());
+            }
+            // Like for checkpoints, the codec of any serializable edge into the input will do.
+            edges
+                .find_map(|edge| edge.restore(queue.elements.clone()))
+                .ok_or_else(|| CheckpointError::NotSerializable {
+                    node: queue.node.clone(),
+                    index: queue.port,
+                })?
+                .with_context(|| {
+                    format!(
+                        "Unable to deserialize elements queued at input {} of node '{}'.",
+                        queue.port, queue.node
+                    )
+                })?;
+        }
+        Ok(())
+    }
+}
+
+impl FlowDefinition {
+    /// Builds the flow and restores `checkpoint` into it.
+    pub fn restore(
+        &self,
+        registry: &NodeRegistry,
+        context: State<Context>,
+        checkpoint: &Checkpoint,
+    ) -> Result<Flow> {
+        let flow = self.build(registry, context)?;
+        flow.restore(checkpoint)?;
+        Ok(flow)
+    }
+}
@@ -0,0 +1,167 @@
+use anyhow::{Context as AnyhowContext, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{
+    node::{Context, State},
+    sched::{
+        flow::{Flow, NodeId},
+        registry::NodeRegistry,
+        version::Version,
+    },
+};
+
+#[derive(Error, Debug)]
+pub enum DefinitionError {
+    #[error("No node kind '{0}' is registered.")]
+    UnknownKind(String),
+
+    #[error("No element type '{0}' is registered.")]
+    UnknownType(String),
+
+    #[error("The definition contains no node named '{0}'.")]
+    UnknownNode(String),
+
+    #[error("Invalid props for node '{node}' at '{field}': {message}")]
+    InvalidProps {
+        node: String,
+        /// A JSON pointer to the offending field, `/` for the props themselves.
+        field: String,
+        message: String,
+    },
+
+    #[error("The subflow kind '{0}' contains itself.")]
+    RecursiveSubflow(String),
+
+    #[error("The definition requires runtime version {required}, which is incompatible with {found}.")]
+    IncompatibleRuntime { required: Version, found: Version },
+}
+
+/// A serializable description of a [`Flow`], e.g. loaded from JSON.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlowDefinition {
+    pub name: String,
+    pub version: Version,
+    /// The runtime version the definition was written for, see [`Version::runtime`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runtime: Option<Version>,
+    /// The node library version the definition was written for, see
+    /// [`NodeRegistry::library_version`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub library: Option<Version>,
+    #[serde(default)]
+    pub nodes: Vec<NodeDefinition>,
+    #[serde(default)]
+    pub edges: Vec<EdgeDefinition>,
+    /// Inner inputs exposed when the flow is used as a subflow.
+    #[serde(default)]
+    pub inputs: Vec<PortDefinition>,
+    /// Inner outputs exposed when the flow is used as a subflow.
+    #[serde(default)]
+    pub outputs: Vec<PortDefinition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeDefinition {
+    pub name: String,
+    /// The kind the node is registered with in the [`NodeRegistry`].
+    pub kind: String,
+    #[serde(default)]
+    pub props: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EdgeDefinition {
+    pub from: EndpointDefinition,
+    pub to: EndpointDefinition,
+    /// The name the element type is registered with in the [`NodeRegistry`].
+    #[serde(rename = "type")]
+    pub element_type: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EndpointDefinition {
+    pub node: String,
+    pub port: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortDefinition {
+    pub name: String,
+    pub node: String,
+    pub port: usize,
+}
+
+impl FlowDefinition {
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).context("Unable to parse flow definition.")
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("Unable to serialize flow definition.")
+    }
+
+    /// Constructs all nodes and connects all edges of the definition.
+    ///
+    /// Fails if the definition requires a runtime that is incompatible with this one. The props
+    /// of all nodes are validated before the first node is constructed. Outdated definitions are
+    /// migrated first, see [`FlowDefinition::migrate`].
+    pub fn build(&self, registry: &NodeRegistry, context: State<Context>) -> Result<Flow> {
+        self.migrated(registry)?.build_migrated(registry, context)
+    }
+
+    fn build_migrated(&self, registry: &NodeRegistry, context: State<Context>) -> Result<Flow> {
+        if let Some(required) = &self.runtime {
+            let found = Version::runtime();
+            if !found.is_compatible_with(required) {
+                return Err(DefinitionError::IncompatibleRuntime {
+                    required: required.clone(),
+                    found,
+                }
+                .into());
+            }
+        }
+
+        for node in &self.nodes {
+            registry.validate_props(&node.name, &node.kind, &node.props)?;
+        }
+
+        let mut flow = Flow::new(&self.name, self.version.clone());
+        for node in &self.nodes {
+            registry.add_node(
+                &mut flow,
+                &node.kind,
+                &node.name,
+                context.clone(),
+                node.props.clone(),
+            )?;
+        }
+
+        for edge in &self.edges {
+            let from = node_id(&flow, &edge.from.node)?;
+            let to = node_id(&flow, &edge.to.node)?;
+            registry
+                .connect(
+                    &mut flow,
+                    &edge.element_type,
+                    from,
+                    edge.from.port,
+                    to,
+                    edge.to.port,
+                )
+                .with_context(|| {
+                    format!(
+                        "Unable to connect '{}' to '{}'.",
+                        edge.from.node, edge.to.node
+                    )
+                })?;
+        }
+        Ok(flow)
+    }
+}
+
+pub(crate) fn node_id(flow: &Flow, name: &str) -> Result<NodeId, DefinitionError> {
+    flow.node_by_name(name)
+        .ok_or_else(|| DefinitionError::UnknownNode(name.into()))
+}
@@ -0,0 +1,187 @@
+use anyhow::{anyhow, Context as AnyhowContext, Result};
+use serde_json::Value;
+use std::sync::{
+    mpsc::{channel, Receiver},
+    Arc, Mutex,
+};
+
+use crate::{
+    checkpoint::Checkpoint,
+    connection::RuntimeConnectable,
+    definition::node_id,
+    flow::Flow,
+    node::{ChangeObserver, Node},
+};
+
+/// A queued change, which sends its outcome to its [`PendingChange`].
+type Change = Box<dyn FnOnce(&mut Flow) + Send>;
+
+/// The changes waiting for the executor. A closed queue drops new changes, so that waiting for
+/// them fails instead of blocking.
+struct Queue {
+    changes: Vec<Change>,
+    closed: bool,
+}
+
+/// Modifies a flow while it is run by an [`Executor`](crate::executor::Executor).
+///
+/// Changes are queued and applied by the executor between two epochs. Nodes are referred to by
+/// their name, so that changes can refer to nodes added by earlier changes. Changes submitted
+/// after the executor stopped fail.
+#[derive(Clone)]
+pub struct FlowEditor {
+    queue: Arc<Mutex<Queue>>,
+    observer: Arc<Mutex<dyn ChangeObserver>>,
+}
+
+/// The outcome of a queued change, available once the executor has applied it.
+pub struct PendingChange<T = ()>(Receiver<Result<T>>);
+
+impl<T> PendingChange<T> {
+    /// Blocks until the change is applied.
+    pub fn wait(self) -> Result<T> {
+        self.0
+            .recv()
+            .map_err(|_| anyhow!("The executor stopped before applying the change."))?
+    }
+}
+
+impl FlowEditor {
+    pub(crate) fn new(observer: Arc<Mutex<dyn ChangeObserver>>) -> Self {
+        Self {
+            queue: Arc::new(Mutex::new(Queue {
+                changes: Vec::new(),
+                closed: false,
+            })),
+            observer,
+        }
+    }
+
+    /// Adds a node and calls its `on_init` and `on_ready`. The node is removed again if either
+    /// fails.
+    pub fn add_node<T>(&self, node: T) -> PendingChange
+    where
+        T: Node + RuntimeConnectable,
+    {
+        self.push(move |flow| {
+            let id = flow.add_node(node)?;
+            let handle = flow.handle(id).unwrap();
+            if let Err(err) = handle.init().and_then(|_| handle.ready()) {
+                flow.remove_node(id)?;
+                return Err(err);
+            }
+            Ok(())
+        })
+    }
+
+    /// Calls `on_shutdown` of a node and removes it together with its edges.
+    pub fn remove_node(&self, name: &str) -> PendingChange {
+        let name = name.to_string();
+        self.push(move |flow| {
+            let id = node_id(flow, &name)?;
+            let handle = flow.remove_node(id)?;
+            handle
+                .shutdown()
+                .with_context(|| format!("Removed node '{}' did not shut down cleanly.", name))
+        })
+    }
+
+    /// See [`Flow::replace_node`].
+    pub fn replace_node<T>(&self, name: &str, node: T) -> PendingChange
+    where
+        T: Node + RuntimeConnectable,
+    {
+        let name = name.to_string();
+        self.push(move |flow| {
+            let id = node_id(flow, &name)?;
+            flow.replace_node(id, node).map(|_| ())
+        })
+    }
+
+    /// Pushes new props to a node between two updates, see [`Flow::configure`].
+    pub fn configure(&self, name: &str, props: Value) -> PendingChange {
+        let name = name.to_string();
+        self.push(move |flow| {
+            let id = node_id(flow, &name)?;
+            flow.configure(id, props)
+        })
+    }
+
+    /// See [`Flow::connect`].
+    pub fn connect<T>(
+        &self,
+        out_node: &str,
+        out_port: usize,
+        in_node: &str,
+        in_port: usize,
+    ) -> PendingChange
+    where
+        T: Send + 'static,
+    {
+        let (out_node, in_node) = (out_node.to_string(), in_node.to_string());
+        self.push(move |flow| {
+            let out_id = node_id(flow, &out_node)?;
+            let in_id = node_id(flow, &in_node)?;
+            Ok(flow.connect::<T>(out_id, out_port, in_id, in_port)?)
+        })
+    }
+
+    /// See [`Flow::disconnect`].
+    pub fn disconnect(&self, out_node: &str, out_port: usize) -> PendingChange {
+        let out_node = out_node.to_string();
+        self.push(move |flow| {
+            let out_id = node_id(flow, &out_node)?;
+            Ok(flow.disconnect(out_id, out_port)?)
+        })
+    }
+
+    /// Takes a checkpoint of the flow between two epochs, see [`Flow::checkpoint`].
+    pub fn checkpoint(&self) -> PendingChange<Checkpoint> {
+        self.push(|flow| flow.checkpoint())
+    }
+
+    /// Whether changes are waiting to be applied.
+    pub(crate) fn has_changes(&self) -> bool {
+        !self.queue.lock().unwrap().changes.is_empty()
+    }
+
+    /// Applies all queued changes in order.
+    pub(crate) fn apply(&self, flow: &mut Flow) {
+        let changes: Vec<_> = self.queue.lock().unwrap().changes.drain(..).collect();
+        for change in changes {
+            change(flow);
+        }
+    }
+
+    /// Accepts changes again, called when an executor starts running a flow.
+    pub(crate) fn open(&self) {
+        self.queue.lock().unwrap().closed = false;
+    }
+
+    /// Drops all queued changes and all changes submitted until the editor is opened again, so
+    /// that waiting for them fails. Called when an executor stops.
+    pub(crate) fn close(&self) {
+        let mut queue = self.queue.lock().unwrap();
+        queue.closed = true;
+        queue.changes.clear();
+    }
+
+    fn push<T, F>(&self, change: F) -> PendingChange<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Flow) -> Result<T> + Send + 'static,
+    {
+        let (sender, receiver) = channel();
+        {
+            let mut queue = self.queue.lock().unwrap();
+            if queue.closed {
+                return PendingChange(receiver);
+            }
+            queue.changes.push(Box::new(move |flow| {
+                let _ = sender.send(change(flow));
+            }));
+        }
+        self.observer.lock().unwrap().on_change();
+        PendingChange(receiver)
+    }
+}
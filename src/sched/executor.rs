@@ -1,11 +1,16 @@
 use crate::{
-    sched::flow::Flow,
-    node::{ChangeObserver, Context, State},
+    sched::{
+        editor::FlowEditor,
+        flow::{Flow, FlowError},
+        metrics::Metrics,
+    },
+    node::{ChangeObserver, Clock, Context, State},
     scheduler::{Scheduler, SchedulingInfo},
 };
 use std::{
     fmt,
     sync::{Arc, Condvar, Mutex},
+    time::Instant,
 };
 use threadpool::ThreadPool;
 use anyhow::{Context as AnyhowContext, Result};
@@ -19,6 +24,7 @@ pub enum ExecutorState {
 
 pub struct ExecutionController {
     state: ExecutorState,
+    epoch: u64,
     cancellation_requested: bool,
     condition: Arc<(Mutex<bool>, Condvar)>,
 }
@@ -37,24 +43,29 @@ impl ExecutionController {
     pub fn new(condition: Arc<(Mutex<bool>, Condvar)>) -> Self {
         Self {
             state: ExecutorState::Ready,
+            epoch: 0,
             cancellation_requested: false,
-            condition: condition,
+            condition,
         }
     }
 
     pub fn cancel(&mut self) {
         self.cancellation_requested = true;
-        if self.state == ExecutorState::Sleeping {
-            self.wakeup();
-        }
+        // Wake up unconditionally, the executor might be about to fall asleep.
+        self.wakeup();
     }
 
     pub fn state(&self) -> ExecutorState {
         self.state
     }
 
+    /// The number of epochs started so far.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
     fn set_state(&mut self, s: ExecutorState) {
-        println!("{}", s);
+        debug!(state = %s, "Executor changed state.");
         self.state = s
     }
 
@@ -72,6 +83,8 @@ impl ExecutionController {
 
 struct ExecutionHibernator {
     num_epochs_to_do: i32,
+    /// The earliest update requested by a node, see [`Context::request_update_at`].
+    deadline: Option<Instant>,
     condition: Arc<(Mutex<bool>, Condvar)>,
 }
 
@@ -79,30 +92,68 @@ impl ExecutionHibernator {
     pub fn new(condition: Arc<(Mutex<bool>, Condvar)>) -> Self {
         Self {
             num_epochs_to_do: 0,
-            condition: condition,
+            deadline: None,
+            condition,
         }
     }
 
-    fn sleep_if_possible(&mut self, controller: Arc<Mutex<ExecutionController>>) {
-        self.num_epochs_to_do = 0.max(self.num_epochs_to_do - 1);
-
-        if self.num_epochs_to_do != 0 {
-            return;
-        }
+    /// Sleeps until the next change or requested update, unless epochs are left to do.
+    /// Requested updates are due by `clock`, the clock of the context the nodes act on.
+    ///
+    /// The hibernator is not locked while sleeping, so that observed changes can wake it up.
+    fn sleep_if_possible(
+        hibernator: &Arc<Mutex<Self>>,
+        controller: Arc<Mutex<ExecutionController>>,
+        clock: &Clock,
+    ) {
+        let (condition, deadline) = {
+            let mut hibernator = hibernator.lock().unwrap();
+            hibernator.num_epochs_to_do = 0.max(hibernator.num_epochs_to_do - 1);
+
+            if hibernator.num_epochs_to_do != 0 || hibernator.take_due_deadline(clock.now()) {
+                return;
+            }
+            (hibernator.condition.clone(), hibernator.deadline)
+        };
 
         controller
             .lock()
             .unwrap()
             .set_state(ExecutorState::Sleeping);
-        self.sleep();
+        Self::sleep(&condition, deadline, clock);
+        hibernator.lock().unwrap().take_due_deadline(clock.now());
         controller.lock().unwrap().set_state(ExecutorState::Running);
     }
 
-    fn sleep(&mut self) {
-        let (lock, cvar) = &*self.condition;
+    /// Waits for a wakeup or until `deadline` is reached on `clock`.
+    ///
+    /// A manual clock is checked again once the time left until the deadline passed in real
+    /// time, so an executor on a clock that is not advanced sleeps instead of running epochs.
+    fn sleep(condition: &(Mutex<bool>, Condvar), deadline: Option<Instant>, clock: &Clock) {
+        let (lock, cvar) = condition;
         let mut ready = lock.lock().unwrap();
         while !*ready {
-            ready = cvar.wait(ready).unwrap();
+            ready = match deadline {
+                Some(deadline) => match deadline.checked_duration_since(clock.now()) {
+                    Some(timeout) if !timeout.is_zero() => {
+                        cvar.wait_timeout(ready, timeout).unwrap().0
+                    }
+                    _ => return,
+                },
+                None => cvar.wait(ready).unwrap(),
+            };
+        }
+        *ready = false;
+    }
+
+    /// Clears the requested update if it is due at `now` and returns whether it was.
+    fn take_due_deadline(&mut self, now: Instant) -> bool {
+        match self.deadline {
+            Some(deadline) if deadline <= now => {
+                self.deadline = None;
+                true
+            }
+            _ => false,
         }
     }
 
@@ -119,6 +170,15 @@ impl ChangeObserver for ExecutionHibernator {
         self.num_epochs_to_do = 1; // For now just a single epoch per change.
         self.wakeup();
     }
+
+    fn on_update_requested(&mut self, at: Instant) {
+        if self.deadline.is_none_or(|deadline| at < deadline) {
+            self.deadline = Some(at);
+            // Wakes up an executor sleeping until a later deadline, so that it sleeps again
+            // until this one.
+            self.wakeup();
+        }
+    }
 }
 
 pub trait Executor {
@@ -127,6 +187,12 @@ pub trait Executor {
         S: Scheduler + std::marker::Send;
 
     fn controller(&self) -> Arc<Mutex<ExecutionController>>;
+
+    /// Returns an editor to modify the flow while it is running.
+    fn editor(&self) -> FlowEditor;
+
+    /// Returns the metrics collected while running the flow.
+    fn metrics(&self) -> Metrics;
 }
 
 /// A Send + Sync thread pool.
@@ -151,22 +217,38 @@ impl SyncThreadPool {
             .expect("could not lock thread pool mutex")
             .execute(job)
     }
+
+    /// Blocks until all jobs executed so far are done.
+    pub fn join(&self) {
+        self.pool
+            .lock()
+            .expect("could not lock thread pool mutex")
+            .join()
+    }
 }
 
 pub struct MultiThreadedExecutor {
     thread_pool: SyncThreadPool,
     controller: Arc<Mutex<ExecutionController>>,
     hibernator: Arc<Mutex<ExecutionHibernator>>,
+    editor: FlowEditor,
+    metrics: Metrics,
+    context: State<Context>,
 }
 
 impl MultiThreadedExecutor {
     pub fn new(num_threads: usize, context: State<Context>) -> Self {
         let condition = Arc::new((Mutex::new(false), Condvar::new()));
 
+        let hibernator = Arc::new(Mutex::new(ExecutionHibernator::new(condition.clone())));
+
         let res = Self {
             thread_pool: SyncThreadPool::new(num_threads),
             controller: Arc::new(Mutex::new(ExecutionController::new(condition.clone()))),
-            hibernator: Arc::new(Mutex::new(ExecutionHibernator::new(condition.clone()))),
+            hibernator: hibernator.clone(),
+            editor: FlowEditor::new(hibernator),
+            metrics: Metrics::new(),
+            context: context.clone(),
         };
 
         context
@@ -178,7 +260,7 @@ impl MultiThreadedExecutor {
         res
     }
 
-    fn run_update_loop<S>(&mut self, flow: &Flow, mut scheduler: S)
+    fn run_update_loop<S>(&mut self, flow: &mut Flow, mut scheduler: S)
     where
         S: Scheduler,
     {
@@ -187,54 +269,114 @@ impl MultiThreadedExecutor {
             .unwrap()
             .set_state(ExecutorState::Running);
 
-        let info = SchedulingInfo {
-            num_nodes: flow.num_nodes(),
-            priorities: Vec::new(),
-        };
-
         while !self.controller.lock().unwrap().cancellation_requested() {
+            let epoch = {
+                let mut controller = self.controller.lock().unwrap();
+                controller.epoch += 1;
+                controller.epoch
+            };
+            self.context.0.lock().unwrap().set_epoch(epoch);
+            debug_span!("epoch", epoch);
+
+            // Changes are only applied between epochs, the updates of the previous epoch are
+            // done by now.
+            if self.editor.has_changes() {
+                self.editor.apply(flow);
+            }
+
+            let info = SchedulingInfo {
+                num_nodes: flow.num_nodes(),
+                priorities: Vec::new(),
+            };
+
             scheduler.restart_epoch();
 
             while !scheduler.epoch_is_over(&info) {
                 let node_idx = scheduler.get_next_node_idx(&info);
 
-                if let Some(node) = flow.get_node(node_idx) {
+                if let Some(node) = flow.get_handle(node_idx) {
+                    let metrics = self.metrics.clone();
                     self.thread_pool.execute(move || {
-                        let _ = node.lock().unwrap().update();
+                        let start = Instant::now();
+                        let res = node.update();
+                        metrics.record_update(node.name(), start.elapsed(), res.is_err());
+                        // The update runs outside of the epoch span, so the epoch is passed on.
+                        #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+                        if let Err(err) = &res {
+                            warn!(epoch, node = node.name(), error = %err, "Node update failed.");
+                        }
                     });
                 }
             }
 
-            self.hibernator
-                .lock()
-                .unwrap()
-                .sleep_if_possible(self.controller.clone());
+            // The epoch ends once all of its updates are done, which is also when its metrics
+            // are recorded.
+            self.thread_pool.join();
+            self.metrics.record_epoch(flow);
+
+            let clock = self.context.0.lock().unwrap().clock();
+            ExecutionHibernator::sleep_if_possible(
+                &self.hibernator,
+                self.controller.clone(),
+                &clock,
+            );
         }
+        self.thread_pool.join();
 
         self.controller
             .lock()
             .unwrap()
             .set_state(ExecutorState::Ready);
     }
-}
 
-impl Executor for MultiThreadedExecutor {
-    fn run<S>(&mut self, flow: Flow, scheduler: S) -> Result<()> 
+    fn run_flow<S>(&mut self, flow: &mut Flow, scheduler: S) -> Result<()>
     where
-        S: Scheduler + std::marker::Send,
+        S: Scheduler,
     {
-        flow.init_all().context(format!("Unable to init all nodes."))?; 
+        let report = flow.validate();
+        if report.has_errors() {
+            return Err(FlowError::Invalid(report).into());
+        }
+
+        let started = flow
+            .init_all()
+            .context("Unable to init all nodes.")
+            .and_then(|()| flow.ready_all().context("Unable to make all nodes ready."));
+        if let Err(err) = started {
+            // Nodes initialized before the failure still get shut down. The error of the failed
+            // start is the one reported.
+            let _ = flow.shutdown_all();
+            return Err(err);
+        }
 
-        flow.ready_all().context(format!("Unable to make all nodes ready."))?;
-    
-        self.run_update_loop(&flow, scheduler);
+        self.run_update_loop(flow, scheduler);
 
-        flow.shutdown_all().context(format!("Unable to shutdown all nodes"))?;
+        flow.shutdown_all().context("Unable to shutdown all nodes")?;
 
         Ok(())
     }
+}
+
+impl Executor for MultiThreadedExecutor {
+    fn run<S>(&mut self, mut flow: Flow, scheduler: S) -> Result<()>
+    where
+        S: Scheduler + std::marker::Send,
+    {
+        self.editor.open();
+        let res = self.run_flow(&mut flow, scheduler);
+        self.editor.close();
+        res
+    }
 
     fn controller(&self) -> Arc<Mutex<ExecutionController>> {
         self.controller.clone()
     }
+
+    fn editor(&self) -> FlowEditor {
+        self.editor.clone()
+    }
+
+    fn metrics(&self) -> Metrics {
+        self.metrics.clone()
+    }
 }
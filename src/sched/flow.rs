@@ -1,12 +1,477 @@
-use std::sync::{Arc, Mutex};
 use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::{
+    any::{type_name, Any, TypeId},
+    collections::BTreeSet,
+    fmt,
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+use thiserror::Error;
 
-use crate::{sched::version::Version, node::Node};
+use crate::{
+    connection::{connect, Input, Output, RuntimeConnectable},
+    lifecycle::{LifecycleError, NodeLifecycle},
+    lineage,
+    node::{ConfigError, Node},
+    sched::{registry::PropsSchema, validation::ValidationReport, version::Version},
+};
+
+/// A stable identifier of a node within a [`Flow`].
+///
+/// Unlike the position of a node, the id does not change when other nodes are removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(pub(crate) usize);
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum FlowError {
+    #[error("A node named '{0}' already exists in this flow.")]
+    DuplicateName(String),
+
+    #[error("No node with id {0} exists in this flow.")]
+    UnknownNode(NodeId),
+
+    #[error("Node '{node}' has no {kind} at index {index}.")]
+    UnknownPort {
+        node: String,
+        kind: PortKind,
+        index: usize,
+    },
+
+    #[error(
+        "The {kind} at index {index} of node '{node}' does not carry elements of type {expected}."
+    )]
+    PortTypeMismatch {
+        node: String,
+        kind: PortKind,
+        index: usize,
+        expected: &'static str,
+    },
+
+    #[error("The output at index {index} of node '{node}' is already connected.")]
+    AlreadyConnected { node: String, index: usize },
+
+    #[error("The output at index {index} of node '{node}' is not connected.")]
+    NotConnected { node: String, index: usize },
+
+    #[error("The flow is invalid.\n{0}")]
+    Invalid(ValidationReport),
+
+    #[error("Node '{node}' can not be reconfigured while it is {state}.")]
+    NotConfigurable { node: String, state: NodeLifecycle },
+
+    #[error("Unable to shutdown {} node(s).{}", .0.len(), list_errors(.0))]
+    ShutdownFailed(Vec<anyhow::Error>),
+}
+
+fn list_errors(errors: &[anyhow::Error]) -> String {
+    errors.iter().map(|err| format!("\n{:#}", err)).collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PortKind {
+    Input,
+    Output,
+}
+
+impl fmt::Display for PortKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PortKind::Input => write!(f, "input"),
+            PortKind::Output => write!(f, "output"),
+        }
+    }
+}
+
+/// A port of a node in a [`Flow`], identified by the node and the port index as used by
+/// [`RuntimeConnectable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Port {
+    pub node: NodeId,
+    pub index: usize,
+}
+
+/// Type erased access to the channel behind a recorded edge.
+trait Link: Send + Sync {
+    fn disconnect(&self);
+    fn queue_depth(&self) -> usize;
+
+    /// Disconnects the edge and connects the given ports instead. Returns `None` if the ports do
+    /// not match the element type of the edge, in which case nothing is changed.
+    fn reconnect(&self, output: Rc<dyn Any>, input: Rc<dyn Any>) -> Option<Arc<dyn Link>>;
+
+    /// Encodes the queued elements without removing them. Returns `None` if the element type of
+    /// the edge is not serializable.
+    fn snapshot(&self) -> Option<serde_json::Result<Vec<Value>>>;
+
+    /// Decodes `elements` and appends them to the queue. Returns `None` if the element type of
+    /// the edge is not serializable.
+    fn restore(&self, elements: Vec<Value>) -> Option<serde_json::Result<()>>;
+}
+
+/// Converts the elements of a serializable edge from and to JSON.
+struct Codec<T> {
+    encode: fn(&T) -> serde_json::Result<Value>,
+    decode: fn(Value) -> serde_json::Result<T>,
+}
+
+impl<T> Clone for Codec<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Codec<T> {}
+
+impl<T: Serialize + DeserializeOwned> Codec<T> {
+    fn json() -> Self {
+        Self {
+            encode: |elem| serde_json::to_value(elem),
+            decode: |value| serde_json::from_value(value),
+        }
+    }
+}
+
+struct TypedLink<T> {
+    output: Output<T>,
+    input: Mutex<Input<T>>,
+    codec: Option<Codec<T>>,
+}
+
+impl<T: Send + 'static> Link for TypedLink<T> {
+    fn disconnect(&self) {
+        self.output.clone().unset();
+    }
+
+    fn queue_depth(&self) -> usize {
+        self.input.lock().unwrap().len()
+    }
+
+    fn reconnect(&self, output: Rc<dyn Any>, input: Rc<dyn Any>) -> Option<Arc<dyn Link>> {
+        let output = output.downcast::<Output<T>>().ok()?.as_ref().clone();
+        let input = input.downcast::<Input<T>>().ok()?.as_ref().clone();
+        self.disconnect();
+        connect(output.clone(), input.clone());
+        Some(Arc::new(TypedLink {
+            output,
+            input: Mutex::new(input),
+            codec: self.codec,
+        }))
+    }
+
+    fn snapshot(&self) -> Option<serde_json::Result<Vec<Value>>> {
+        let codec = self.codec?;
+        let input = self.input.lock().unwrap();
+        let mut entries = Vec::new();
+        while let Ok(entry) = input.next_entry() {
+            entries.push(entry);
+        }
+        let encoded = entries.iter().map(|(elem, _)| (codec.encode)(elem)).collect();
+        for entry in entries {
+            let _ = input.send_entry(entry);
+        }
+        Some(encoded)
+    }
+
+    fn restore(&self, elements: Vec<Value>) -> Option<serde_json::Result<()>> {
+        let codec = self.codec?;
+        let decoded = elements
+            .into_iter()
+            .map(codec.decode)
+            .collect::<serde_json::Result<Vec<T>>>();
+        Some(decoded.map(|elements| {
+            let input = self.input.lock().unwrap();
+            for elem in elements {
+                let _ = input.send(elem);
+            }
+        }))
+    }
+}
+
+/// An edge of the flow graph, connecting an output port to an input port.
+#[derive(Clone)]
+pub struct FlowEdge {
+    from: Port,
+    to: Port,
+    type_id: TypeId,
+    type_name: &'static str,
+    pub(crate) output_type: TypeId,
+    pub(crate) input_type: TypeId,
+    link: Arc<dyn Link>,
+}
+
+impl FlowEdge {
+    /// The output port the edge starts at.
+    pub fn from(&self) -> Port {
+        self.from
+    }
+
+    /// The input port the edge ends at.
+    pub fn to(&self) -> Port {
+        self.to
+    }
+
+    /// The [`TypeId`] of the elements sent along the edge.
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// The name of the type of the elements sent along the edge.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// The number of elements currently queued at the input. This includes elements sent to the
+    /// input from outside of this edge.
+    pub fn queue_depth(&self) -> usize {
+        self.link.queue_depth()
+    }
+
+    pub(crate) fn snapshot(&self) -> Option<serde_json::Result<Vec<Value>>> {
+        self.link.snapshot()
+    }
+
+    pub(crate) fn restore(&self, elements: Vec<Value>) -> Option<serde_json::Result<()>> {
+        self.link.restore(elements)
+    }
+}
+
+impl fmt::Debug for FlowEdge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FlowEdge")
+            .field("from", &self.from)
+            .field("to", &self.to)
+            .field("type_name", &self.type_name)
+            .finish()
+    }
+}
+
+/// A node together with its lifecycle state.
+///
+/// All lifecycle methods of the node should be called through the handle, so that illegal
+/// transitions (e.g. an `update` before `on_ready`) are rejected with a [`LifecycleError`].
+#[derive(Clone)]
+pub struct NodeHandle {
+    id: NodeId,
+    name: String,
+    type_name: &'static str,
+    node: Arc<Mutex<dyn Node>>,
+    ports: Arc<Mutex<dyn RuntimeConnectable + Send>>,
+    lifecycle: Arc<Mutex<NodeLifecycle>>,
+    config: Arc<Mutex<Value>>,
+    schema: Arc<Mutex<Option<Arc<PropsSchema>>>>,
+}
+
+impl NodeHandle {
+    fn new<T>(id: NodeId, name: String, node: T) -> Self
+    where
+        T: Node + RuntimeConnectable,
+    {
+        let node = Arc::new(Mutex::new(node));
+        Self {
+            id,
+            name,
+            type_name: type_name::<T>(),
+            node: node.clone(),
+            ports: node,
+            lifecycle: Arc::new(Mutex::new(NodeLifecycle::Created)),
+            config: Arc::new(Mutex::new(Value::Null)),
+            schema: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The name of the concrete type of the node.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn node(&self) -> Arc<Mutex<dyn Node>> {
+        self.node.clone()
+    }
+
+    /// Runtime access to the ports of the node.
+    pub fn ports(&self) -> Arc<Mutex<dyn RuntimeConnectable + Send>> {
+        self.ports.clone()
+    }
+
+    pub fn state(&self) -> NodeLifecycle {
+        *self.lifecycle.lock().unwrap()
+    }
+
+    /// The props the node was constructed with or last reconfigured with, `null` if unknown.
+    pub fn config(&self) -> Value {
+        self.config.lock().unwrap().clone()
+    }
+
+    pub(crate) fn set_config(&self, props: Value) {
+        *self.config.lock().unwrap() = props;
+    }
+
+    /// Sets the schema new props are validated against, see [`NodeHandle::configure`].
+    pub(crate) fn set_schema(&self, schema: Option<Arc<PropsSchema>>) {
+        *self.schema.lock().unwrap() = schema;
+    }
+
+    /// Pushes new props to a `Ready` or `Running` node, see [`Node::on_config_change`]. Nodes
+    /// constructed by a [`NodeRegistry`](crate::registry::NodeRegistry) validate the props
+    /// against the schema of their kind first.
+    ///
+    /// If the node rejects them, the previous props are pushed again, if they are known, and the
+    /// state exported before the change is imported again, to roll back changes the node might
+    /// have applied partially. Nodes that do not support config changes have nothing to roll
+    /// back.
+    pub fn configure(&self, props: Value) -> Result<()> {
+        let node = self.node.lock().unwrap();
+        let state = self.state();
+        if !matches!(state, NodeLifecycle::Ready | NodeLifecycle::Running) {
+            return Err(FlowError::NotConfigurable {
+                node: node.name().into(),
+                state,
+            }
+            .into());
+        }
+        if let Some(schema) = &*self.schema.lock().unwrap() {
+            schema.validate(node.name(), &props)?;
+        }
+        let mut config = self.config.lock().unwrap();
+        let previous = node
+            .export_state()
+            .with_context(|| format!("Unable to export state of node '{}'.", node.name()))?;
+        let err = match node.on_config_change(props.clone()) {
+            Ok(()) => {
+                *config = props;
+                return Ok(());
+            }
+            Err(err @ ConfigError::Unsupported { .. }) => return Err(err.into()),
+            Err(err) => anyhow::Error::from(err)
+                .context(format!("Unable to reconfigure node '{}'.", node.name())),
+        };
+        if !config.is_null() {
+            if let Err(rollback) = node.on_config_change(config.clone()) {
+                return Err(err.context(format!(
+                    "Unable to roll back the config of node '{}': {}",
+                    node.name(),
+                    rollback
+                )));
+            }
+        }
+        if let Some(previous) = previous {
+            if let Err(rollback) = node.import_state(previous) {
+                return Err(err.context(format!(
+                    "Unable to roll back the state of node '{}': {}",
+                    node.name(),
+                    rollback
+                )));
+            }
+        }
+        Err(err)
+    }
+
+    pub fn init(&self) -> Result<()> {
+        self.advance(
+            NodeLifecycle::Initialized,
+            |n| n.on_init(),
+            |name| format!("Unable to init node '{}'.", name),
+        )
+    }
+
+    pub fn ready(&self) -> Result<()> {
+        self.advance(
+            NodeLifecycle::Ready,
+            |n| n.on_ready(),
+            |name| format!("Unable to make node '{}' ready.", name),
+        )
+    }
+
+    pub fn shutdown(&self) -> Result<()> {
+        self.advance(
+            NodeLifecycle::ShutDown,
+            |n| n.on_shutdown(),
+            |name| format!("Unable to shutdown node '{}'.", name),
+        )
+    }
+
+    /// Updates the node. An update error does not fail the node, it stays `Running`.
+    pub fn update(&self) -> Result<()> {
+        let node = self.node.lock().unwrap();
+        trace!(node = node.name(), "Updating node.");
+        let _lineage = lineage::enter(node.name());
+        self.lifecycle
+            .lock()
+            .unwrap()
+            .transition(node.name(), NodeLifecycle::Running)?;
+        node.update()
+            .with_context(|| format!("Unable to update node '{}'.", node.name()))
+    }
+
+    fn advance<E, F, M>(&self, next: NodeLifecycle, action: F, message: M) -> Result<()>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+        F: FnOnce(&dyn Node) -> Result<(), E>,
+        M: FnOnce(&str) -> String,
+    {
+        let node = self.node.lock().unwrap();
+        let current = self.state();
+        if !current.can_transition_to(next) {
+            return Err(LifecycleError {
+                node: node.name().into(),
+                from: current,
+                to: next,
+            }
+            .into());
+        }
+
+        let res = action(&*node);
+        let mut lifecycle = self.lifecycle.lock().unwrap();
+        match res {
+            Ok(()) => {
+                debug!(node = node.name(), state = %next, "Node changed state.");
+                *lifecycle = next;
+                Ok(())
+            }
+            Err(err) => {
+                warn!(node = node.name(), state = %next, error = %err, "Node failed.");
+                if lifecycle.can_transition_to(NodeLifecycle::Failed) {
+                    *lifecycle = NodeLifecycle::Failed;
+                }
+                Err(err).context(message(node.name()))
+            }
+        }
+    }
+}
+
+impl fmt::Debug for NodeHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("NodeHandle")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("type_name", &self.type_name)
+            .field("state", &self.state())
+            .finish()
+    }
+}
 
 pub struct Flow {
     name: String,
     version: Version,
-    nodes: Vec<Arc<Mutex<dyn Node>>>,
+    nodes: Vec<NodeHandle>,
+    edges: Vec<FlowEdge>,
+    next_id: usize,
 }
 
 impl Flow {
@@ -15,18 +480,399 @@ impl Flow {
             name: name.to_string(),
             version: v,
             nodes: Vec::new(),
+            edges: Vec::new(),
+            next_id: 0,
         }
     }
 
-    pub fn add_node<T>(&mut self, node: T)
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> &Version {
+        &self.version
+    }
+
+    /// Adds a node to the flow. Node names have to be unique within a flow.
+    pub fn add_node<T>(&mut self, node: T) -> Result<NodeId, FlowError>
     where
-        T: Node,
+        T: Node + RuntimeConnectable,
     {
-        self.nodes.push(Arc::new(Mutex::new(node)));
+        let name = node.name().to_string();
+        if self.node_by_name(&name).is_some() {
+            return Err(FlowError::DuplicateName(name));
+        }
+
+        let id = NodeId(self.next_id);
+        self.next_id += 1;
+        debug!(flow = %self.name, node = %name, id = %id, "Added node.");
+        self.nodes.push(NodeHandle::new(id, name, node));
+        Ok(id)
     }
 
+    /// Removes a node and all its edges from the flow and returns its handle.
+    ///
+    /// Outputs of predecessors are disconnected. The node is not shut down, this is left to the
+    /// caller.
+    pub fn remove_node(&mut self, id: NodeId) -> Result<NodeHandle, FlowError> {
+        let pos = self.position(id).ok_or(FlowError::UnknownNode(id))?;
+        self.edges.retain(|e| {
+            let touches = e.from.node == id || e.to.node == id;
+            if touches {
+                e.link.disconnect();
+            }
+            !touches
+        });
+        debug!(flow = %self.name, node = %self.nodes[pos].name, "Removed node.");
+        Ok(self.nodes.remove(pos))
+    }
+
+    /// Replaces a node with `node`, keeping its id and connections, and returns the old handle.
+    ///
+    /// The old node exports its state with [`Node::export_state`], which is imported into the
+    /// replacement. The replacement is then brought into the lifecycle state of the old node and
+    /// the old node is shut down. Only then do the outputs of the replacement take over the
+    /// connections of the old outputs and elements queued at the old inputs move to the new
+    /// inputs. If any step fails, the old node stays in place and the replacement is shut down.
+    /// Ports are matched by index, all ports with recorded edges have to exist with the same
+    /// element type in the replacement.
+    ///
+    /// The inputs of the replacement are new queues. Recorded edges are reconnected to them, but
+    /// clones of the old inputs that were kept outside of the flow, e.g. by a
+    /// [`Feeder`](crate::testing::Feeder) or a test, still send to the old queues, which nobody
+    /// receives from anymore.
+    ///
+    /// Predecessors should not run while a node is replaced, e.g. by replacing it through a
+    /// [`FlowEditor`](crate::editor::FlowEditor).
+    pub fn replace_node<T>(&mut self, id: NodeId, node: T) -> Result<NodeHandle>
+    where
+        T: Node + RuntimeConnectable,
+    {
+        let pos = self.position(id).ok_or(FlowError::UnknownNode(id))?;
+        let name = node.name().to_string();
+        if self.nodes.iter().any(|h| h.id != id && h.name == name) {
+            return Err(FlowError::DuplicateName(name).into());
+        }
+        for edge in self.edges.iter() {
+            if edge.from.node == id {
+                check_port(&node, &name, PortKind::Output, edge.from.index, edge)?;
+            }
+            if edge.to.node == id {
+                check_port(&node, &name, PortKind::Input, edge.to.index, edge)?;
+            }
+        }
+
+        let old = self.nodes[pos].clone();
+        let new = NodeHandle::new(id, name, node);
+        new.set_config(old.config());
+        new.set_schema(old.schema.lock().unwrap().clone());
+        if let Err(err) = Self::take_over(&old, &new) {
+            if matches!(
+                new.state(),
+                NodeLifecycle::Initialized | NodeLifecycle::Ready | NodeLifecycle::Running
+            ) {
+                let _ = new.shutdown();
+            }
+            return Err(err);
+        }
+
+        for edge in self.edges.iter_mut() {
+            if edge.from.node != id && edge.to.node != id {
+                continue;
+            }
+            let ports = |node: NodeId| match node == id {
+                true => new.ports(),
+                false => self.nodes[self.nodes.iter().position(|h| h.id == node).unwrap()].ports(),
+            };
+            let output = ports(edge.from.node)
+                .lock()
+                .unwrap()
+                .output_at(edge.from.index);
+            let input = ports(edge.to.node).lock().unwrap().input_at(edge.to.index);
+            edge.link = edge
+                .link
+                .reconnect(output, input)
+                .expect("Ports of the replacement have been checked.");
+        }
+
+        {
+            let old_ports = old.ports.lock().unwrap();
+            let new_ports = new.ports.lock().unwrap();
+            for index in 0..old_ports.output_len().min(new_ports.output_len()) {
+                old_ports
+                    .dyn_output_at(index)
+                    .move_to(new_ports.output_at(index));
+            }
+            for index in 0..old_ports.input_len().min(new_ports.input_len()) {
+                old_ports
+                    .dyn_input_at(index)
+                    .drain_into(new_ports.input_at(index));
+            }
+        }
+
+        self.nodes[pos] = new;
+        Ok(old)
+    }
+
+    /// Moves the state of `old` into `new`, brings `new` into the lifecycle state of `old` and
+    /// shuts `old` down, the fallible steps of [`Flow::replace_node`].
+    fn take_over(old: &NodeHandle, new: &NodeHandle) -> Result<()> {
+        let state = old
+            .node
+            .lock()
+            .unwrap()
+            .export_state()
+            .with_context(|| format!("Unable to export state of node '{}'.", old.name))?;
+        if let Some(state) = state {
+            new.node
+                .lock()
+                .unwrap()
+                .import_state(state)
+                .with_context(|| format!("Unable to import state into node '{}'.", new.name))?;
+        }
+
+        let old_state = old.state();
+        match old_state {
+            NodeLifecycle::Initialized => new.init()?,
+            NodeLifecycle::Ready | NodeLifecycle::Running => {
+                new.init()?;
+                new.ready()?;
+            }
+            _ => (),
+        }
+        if old_state != NodeLifecycle::Created && old_state != NodeLifecycle::ShutDown {
+            old.shutdown()?;
+        }
+        Ok(())
+    }
+
+    /// Connects the output `out_port` of `out_node` to the input `in_port` of `in_node` and
+    /// records the edge.
+    ///
+    /// Ports are addressed by the indices used by [`RuntimeConnectable`]. An output can only be
+    /// connected to a single input, while an input may receive from several outputs.
+    pub fn connect<T>(
+        &mut self,
+        out_node: NodeId,
+        out_port: usize,
+        in_node: NodeId,
+        in_port: usize,
+    ) -> Result<(), FlowError>
+    where
+        T: Send + 'static,
+    {
+        self.connect_with::<T>(out_node, out_port, in_node, in_port, None)
+    }
+
+    /// Like [`Flow::connect`], but the elements queued on the edge are included in checkpoints
+    /// of the flow.
+    pub fn connect_serializable<T>(
+        &mut self,
+        out_node: NodeId,
+        out_port: usize,
+        in_node: NodeId,
+        in_port: usize,
+    ) -> Result<(), FlowError>
+    where
+        T: Serialize + DeserializeOwned + Send + 'static,
+    {
+        let codec = Some(Codec::json());
+        self.connect_with::<T>(out_node, out_port, in_node, in_port, codec)
+    }
+
+    fn connect_with<T>(
+        &mut self,
+        out_node: NodeId,
+        out_port: usize,
+        in_node: NodeId,
+        in_port: usize,
+        codec: Option<Codec<T>>,
+    ) -> Result<(), FlowError>
+    where
+        T: Send + 'static,
+    {
+        let output: Output<T> = self.port(out_node, PortKind::Output, out_port)?;
+        let input: Input<T> = self.port(in_node, PortKind::Input, in_port)?;
+
+        if output.is_connected() {
+            return Err(FlowError::AlreadyConnected {
+                node: self.nodes[self.position(out_node).unwrap()].name.clone(),
+                index: out_port,
+            });
+        }
+
+        debug!(
+            flow = %self.name,
+            from = %out_node,
+            out_port,
+            to = %in_node,
+            in_port,
+            element_type = type_name::<T>(),
+            "Connected ports."
+        );
+        connect(output.clone(), input.clone());
+        self.edges.push(FlowEdge {
+            from: Port {
+                node: out_node,
+                index: out_port,
+            },
+            to: Port {
+                node: in_node,
+                index: in_port,
+            },
+            type_id: TypeId::of::<T>(),
+            type_name: type_name::<T>(),
+            output_type: TypeId::of::<Output<T>>(),
+            input_type: TypeId::of::<Input<T>>(),
+            link: Arc::new(TypedLink {
+                output,
+                input: Mutex::new(input),
+                codec,
+            }),
+        });
+        Ok(())
+    }
+
+    /// Removes the edge starting at the output `out_port` of `out_node` and disconnects the
+    /// output.
+    pub fn disconnect(&mut self, out_node: NodeId, out_port: usize) -> Result<(), FlowError> {
+        let from = Port {
+            node: out_node,
+            index: out_port,
+        };
+        let pos = self
+            .edges
+            .iter()
+            .position(|e| e.from == from)
+            .ok_or_else(|| match self.handle(out_node) {
+                Some(handle) => FlowError::NotConnected {
+                    node: handle.name.clone(),
+                    index: out_port,
+                },
+                None => FlowError::UnknownNode(out_node),
+            })?;
+        self.edges.remove(pos).link.disconnect();
+        debug!(flow = %self.name, from = %out_node, out_port, "Disconnected output.");
+        Ok(())
+    }
+
+    /// All edges of the flow in the order they were connected.
+    pub fn edges(&self) -> &[FlowEdge] {
+        &self.edges
+    }
+
+    /// Edges ending at `id`.
+    pub fn incoming(&self, id: NodeId) -> impl Iterator<Item = &FlowEdge> {
+        self.edges.iter().filter(move |e| e.to.node == id)
+    }
+
+    /// Edges starting at `id`.
+    pub fn outgoing(&self, id: NodeId) -> impl Iterator<Item = &FlowEdge> {
+        self.edges.iter().filter(move |e| e.from.node == id)
+    }
+
+    /// Nodes with an edge into `id`, without duplicates.
+    pub fn predecessors(&self, id: NodeId) -> Vec<NodeId> {
+        let preds: BTreeSet<NodeId> = self.incoming(id).map(|e| e.from.node).collect();
+        preds.into_iter().collect()
+    }
+
+    /// Nodes with an edge from `id`, without duplicates.
+    pub fn successors(&self, id: NodeId) -> Vec<NodeId> {
+        let succs: BTreeSet<NodeId> = self.outgoing(id).map(|e| e.to.node).collect();
+        succs.into_iter().collect()
+    }
+
+    /// Nodes without incoming edges.
+    pub fn sources(&self) -> Vec<NodeId> {
+        self.node_ids()
+            .filter(|id| self.incoming(*id).next().is_none())
+            .collect()
+    }
+
+    /// Nodes without outgoing edges.
+    pub fn sinks(&self) -> Vec<NodeId> {
+        self.node_ids()
+            .filter(|id| self.outgoing(*id).next().is_none())
+            .collect()
+    }
+
+    /// Returns the node at position `idx`, as used by the [`Scheduler`](crate::scheduler::Scheduler).
     pub fn get_node(&self, idx: usize) -> Option<Arc<Mutex<dyn Node>>> {
-        self.nodes.get(idx).map(|node| node.clone())
+        self.nodes.get(idx).map(NodeHandle::node)
+    }
+
+    /// Returns the handle at position `idx`, as used by the [`Scheduler`](crate::scheduler::Scheduler).
+    pub fn get_handle(&self, idx: usize) -> Option<NodeHandle> {
+        self.nodes.get(idx).cloned()
+    }
+
+    pub fn node(&self, id: NodeId) -> Option<Arc<Mutex<dyn Node>>> {
+        self.handle(id).map(|h| h.node())
+    }
+
+    pub fn handle(&self, id: NodeId) -> Option<NodeHandle> {
+        self.position(id).map(|pos| self.nodes[pos].clone())
+    }
+
+    pub fn node_by_name(&self, name: &str) -> Option<NodeId> {
+        self.nodes.iter().find(|h| h.name == name).map(|h| h.id)
+    }
+
+    /// Pushes new props to a node, see [`NodeHandle::configure`].
+    pub fn configure(&self, id: NodeId, props: Value) -> Result<()> {
+        self.handle(id)
+            .ok_or(FlowError::UnknownNode(id))?
+            .configure(props)
+    }
+
+    pub fn node_state(&self, id: NodeId) -> Option<NodeLifecycle> {
+        self.handle(id).map(|h| h.state())
+    }
+
+    /// Iterates over all nodes in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = &NodeHandle> {
+        self.nodes.iter()
+    }
+
+    pub fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.nodes.iter().map(|h| h.id)
+    }
+
+    fn position(&self, id: NodeId) -> Option<usize> {
+        self.nodes.iter().position(|h| h.id == id)
+    }
+
+    /// Resolves a port of a node and downcasts it to `P`, i.e. `Input<T>` or `Output<T>`.
+    pub(crate) fn port<P>(&self, id: NodeId, kind: PortKind, index: usize) -> Result<P, FlowError>
+    where
+        P: Clone + 'static,
+    {
+        let handle = &self.nodes[self.position(id).ok_or(FlowError::UnknownNode(id))?];
+        let ports = handle.ports.lock().unwrap();
+        let len = match kind {
+            PortKind::Input => ports.input_len(),
+            PortKind::Output => ports.output_len(),
+        };
+        if index >= len {
+            return Err(FlowError::UnknownPort {
+                node: handle.name.clone(),
+                kind,
+                index,
+            });
+        }
+        let port = match kind {
+            PortKind::Input => ports.input_at(index),
+            PortKind::Output => ports.output_at(index),
+        };
+        port.downcast::<P>()
+            .map(|p| (*p).clone())
+            .map_err(|_| FlowError::PortTypeMismatch {
+                node: handle.name.clone(),
+                kind,
+                index,
+                expected: type_name::<P>(),
+            })
     }
 
     pub fn num_nodes(&self) -> usize {
@@ -35,38 +881,64 @@ impl Flow {
 
     pub fn init_all(&self) -> Result<()> {
         for n in &self.nodes {
-            let name :String = n.lock().unwrap().name().to_string();
-            n
-                .lock()
-                .unwrap()
-                .on_init()
-                .context(format!("Unable to init node '{}'.", name))?;
+            n.init()?;
         }
         Ok(())
     }
 
+    /// Shuts down every node, also if shutting down another one fails. Nodes that were never
+    /// initialized or are already shut down are skipped.
     pub fn shutdown_all(&self) -> Result<()> {
-        for n in &self.nodes {
-            let name :String = n.lock().unwrap().name().to_string();
-            n
-                .lock()
-                .unwrap()
-                .on_shutdown()
-                .context(format!("Unable to shutdown node '{}'.", name))?;
+        let errors: Vec<_> = self
+            .nodes
+            .iter()
+            .filter(|n| !matches!(n.state(), NodeLifecycle::Created | NodeLifecycle::ShutDown))
+            .filter_map(|n| n.shutdown().err())
+            .collect();
+        if !errors.is_empty() {
+            return Err(FlowError::ShutdownFailed(errors).into());
         }
         Ok(())
-
     }
 
     pub fn ready_all(&self) -> Result<()> {
         for n in &self.nodes {
-            let name :String = n.lock().unwrap().name().to_string();
-            n
-                .lock()
-                .unwrap()
-                .on_ready()
-                .context(format!("Unable to make node '{}' ready.", name))?;
+            n.ready()?;
         }
         Ok(())
     }
 }
+
+/// Checks that `node` has a port at `index` matching the element type of `edge`.
+fn check_port<T: RuntimeConnectable>(
+    node: &T,
+    name: &str,
+    kind: PortKind,
+    index: usize,
+    edge: &FlowEdge,
+) -> Result<(), FlowError> {
+    let (len, expected) = match kind {
+        PortKind::Input => (node.input_len(), edge.input_type),
+        PortKind::Output => (node.output_len(), edge.output_type),
+    };
+    if index >= len {
+        return Err(FlowError::UnknownPort {
+            node: name.into(),
+            kind,
+            index,
+        });
+    }
+    let port = match kind {
+        PortKind::Input => node.input_at(index),
+        PortKind::Output => node.output_at(index),
+    };
+    if (*port).type_id() != expected {
+        return Err(FlowError::PortTypeMismatch {
+            node: name.into(),
+            kind,
+            index,
+            expected: edge.type_name,
+        });
+    }
+    Ok(())
+}
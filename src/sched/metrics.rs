@@ -0,0 +1,185 @@
+use std::{
+    collections::BTreeMap,
+    io,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::sched::flow::Flow;
+
+/// Update statistics of a single node.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NodeMetrics {
+    pub updates: u64,
+    pub errors: u64,
+    pub total_latency: Duration,
+    pub max_latency: Duration,
+}
+
+impl NodeMetrics {
+    pub fn mean_latency(&self) -> Duration {
+        match self.updates {
+            0 => Duration::ZERO,
+            n => Duration::from_secs_f64(self.total_latency.as_secs_f64() / n as f64),
+        }
+    }
+}
+
+/// The queue depth of an edge at the end of the last epoch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdgeMetrics {
+    pub from: String,
+    pub from_port: usize,
+    pub to: String,
+    pub to_port: usize,
+    pub queue_depth: usize,
+}
+
+/// A consistent copy of all metrics, see [`Metrics::snapshot`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricsSnapshot {
+    pub epochs: u64,
+    /// Node metrics by node name.
+    pub nodes: BTreeMap<String, NodeMetrics>,
+    pub edges: Vec<EdgeMetrics>,
+}
+
+/// Collects metrics of a running flow. Clones share the collected metrics.
+///
+/// The [`MultiThreadedExecutor`](crate::executor::MultiThreadedExecutor) records every update
+/// and the queue depths at the end of every epoch, once all of its updates are done.
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    inner: Arc<Mutex<MetricsSnapshot>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records an update of the node `name` that took `latency`.
+    pub fn record_update(&self, name: &str, latency: Duration, failed: bool) {
+        let mut inner = self.inner.lock().unwrap();
+        let node = inner.nodes.entry(name.into()).or_default();
+        node.updates += 1;
+        node.errors += u64::from(failed);
+        node.total_latency += latency;
+        node.max_latency = node.max_latency.max(latency);
+    }
+
+    /// Records the end of an epoch together with the current queue depths of `flow`.
+    pub fn record_epoch(&self, flow: &Flow) {
+        let name = |id| {
+            flow.handle(id)
+                .map(|handle| handle.name().to_string())
+                .unwrap_or_default()
+        };
+        let edges = flow
+            .edges()
+            .iter()
+            .map(|edge| EdgeMetrics {
+                from: name(edge.from().node),
+                from_port: edge.from().index,
+                to: name(edge.to().node),
+                to_port: edge.to().index,
+                queue_depth: edge.queue_depth(),
+            })
+            .collect();
+
+        let mut inner = self.inner.lock().unwrap();
+        inner.epochs += 1;
+        inner.edges = edges;
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        self.inner.lock().unwrap().clone()
+    }
+}
+
+impl MetricsSnapshot {
+    /// Writes the metrics in the Prometheus text exposition format.
+    pub fn write_prometheus<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        header(writer, "flowrs_epochs_total", "counter", "Number of finished epochs.")?;
+        writeln!(writer, "flowrs_epochs_total {}", self.epochs)?;
+
+        let node_metrics: [NodeMetric; 5] = [
+            (
+                "flowrs_node_updates_total",
+                "counter",
+                "Number of node updates.",
+                |m| m.updates.to_string(),
+            ),
+            (
+                "flowrs_node_update_errors_total",
+                "counter",
+                "Number of failed node updates.",
+                |m| m.errors.to_string(),
+            ),
+            (
+                "flowrs_node_update_seconds_total",
+                "counter",
+                "Total time spent updating a node.",
+                |m| m.total_latency.as_secs_f64().to_string(),
+            ),
+            (
+                "flowrs_node_update_seconds_mean",
+                "gauge",
+                "Mean time spent per node update.",
+                |m| m.mean_latency().as_secs_f64().to_string(),
+            ),
+            (
+                "flowrs_node_update_seconds_max",
+                "gauge",
+                "Longest node update.",
+                |m| m.max_latency.as_secs_f64().to_string(),
+            ),
+        ];
+        for (metric, kind, help, value) in node_metrics {
+            header(writer, metric, kind, help)?;
+            for (name, node) in &self.nodes {
+                writeln!(
+                    writer,
+                    "{}{{node=\"{}\"}} {}",
+                    metric,
+                    escape_label(name),
+                    value(node)
+                )?;
+            }
+        }
+
+        header(
+            writer,
+            "flowrs_edge_queue_depth",
+            "gauge",
+            "Number of elements queued on an edge.",
+        )?;
+        for edge in &self.edges {
+            writeln!(
+                writer,
+                "flowrs_edge_queue_depth{{from=\"{}\",from_port=\"{}\",to=\"{}\",to_port=\"{}\"}} {}",
+                escape_label(&edge.from),
+                edge.from_port,
+                escape_label(&edge.to),
+                edge.to_port,
+                edge.queue_depth
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Name, type, help text and value of a per-node metric.
+type NodeMetric = (&'static str, &'static str, &'static str, fn(&NodeMetrics) -> String);
+
+fn header<W: io::Write>(writer: &mut W, metric: &str, kind: &str, help: &str) -> io::Result<()> {
+    writeln!(writer, "# HELP {} {}", metric, help)?;
+    writeln!(writer, "# TYPE {} {}", metric, kind)
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,174 @@
+use anyhow::{Context as AnyhowContext, Result};
+use std::fmt;
+use thiserror::Error;
+
+use crate::sched::{definition::FlowDefinition, registry::NodeRegistry, version::Version};
+
+type MigrateFn = dyn Fn(&mut FlowDefinition, &str) -> Result<()> + Send + Sync;
+
+#[derive(Error, Debug)]
+pub enum MigrationError {
+    #[error(
+        "Flow '{flow}' was written for node library {found}, which is newer than {supported}."
+    )]
+    NewerLibrary {
+        flow: String,
+        found: Version,
+        supported: Version,
+    },
+}
+
+/// Upgrades the nodes of one kind in definitions written for an older node library, e.g. by
+/// renaming the kind, a prop or a port. Migrations are registered per node kind with a
+/// [`NodeRegistry`], see [`NodeRegistry::register_migration`].
+pub struct Migration {
+    version: Version,
+    description: String,
+    migrate: Box<MigrateFn>,
+}
+
+impl Migration {
+    /// A migration to the node library `version`. `migrate` is called with the definition and
+    /// the name of each node of the kind the migration is registered for. It may change the
+    /// node as well as the edges and exposed ports referring to it.
+    pub fn new<F>(version: Version, description: &str, migrate: F) -> Self
+    where
+        F: Fn(&mut FlowDefinition, &str) -> Result<()> + Send + Sync + 'static,
+    {
+        Self {
+            version,
+            description: description.into(),
+            migrate: Box::new(migrate),
+        }
+    }
+
+    /// The node library version that introduced the change.
+    pub fn version(&self) -> &Version {
+        &self.version
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+/// A migration that was applied to a node of a definition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppliedMigration {
+    pub node: String,
+    /// The kind of the node before the migration.
+    pub kind: String,
+    pub version: Version,
+    pub description: String,
+}
+
+/// The migrations applied to a definition by [`FlowDefinition::migrate`], in order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationReport {
+    pub flow: String,
+    /// The node library version the definition was migrated to.
+    pub library: Version,
+    pub applied: Vec<AppliedMigration>,
+}
+
+impl MigrationReport {
+    pub fn is_empty(&self) -> bool {
+        self.applied.is_empty()
+    }
+}
+
+impl fmt::Display for MigrationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.applied.is_empty() {
+            return write!(f, "Flow '{}' is up to date.", self.flow);
+        }
+        write!(
+            f,
+            "Migrated flow '{}' to node library {}:",
+            self.flow, self.library
+        )?;
+        for migration in &self.applied {
+            write!(
+                f,
+                "\n  {} ({}) {}: {}",
+                migration.node, migration.kind, migration.version, migration.description
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl FlowDefinition {
+    /// Parses a definition and applies all migrations `registry` has for it.
+    pub fn load(json: &str, registry: &NodeRegistry) -> Result<(Self, MigrationReport)> {
+        let mut definition = Self::from_json(json)?;
+        let report = definition.migrate(registry)?;
+        Ok((definition, report))
+    }
+
+    /// Upgrades the definition to the node library version of `registry`, see
+    /// [`NodeRegistry::library_version`].
+    ///
+    /// The migrations registered for the kinds of its nodes are applied if they are newer than
+    /// the library version recorded in the definition and not newer than the one of `registry`,
+    /// oldest first. Definitions without a recorded version predate library versions and get all
+    /// of them. Afterwards, the definition records the library version of `registry`.
+    ///
+    /// The definition is only changed if all migrations succeed.
+    pub fn migrate(&mut self, registry: &NodeRegistry) -> Result<MigrationReport> {
+        let supported = registry.library_version();
+        let found = self.library.clone().unwrap_or(Version::new(0, 0, 0));
+        if found > *supported {
+            return Err(MigrationError::NewerLibrary {
+                flow: self.name.clone(),
+                found,
+                supported: supported.clone(),
+            }
+            .into());
+        }
+
+        let mut migrated = self.clone();
+        let mut applied = Vec::new();
+        for (kind, migration) in registry.migrations() {
+            if migration.version <= found || migration.version > *supported {
+                continue;
+            }
+            // Earlier migrations may have changed the kind of a node.
+            let nodes: Vec<_> = migrated
+                .nodes
+                .iter()
+                .filter(|node| node.kind == kind)
+                .map(|node| node.name.clone())
+                .collect();
+            for node in nodes {
+                (migration.migrate)(&mut migrated, &node).with_context(|| {
+                    format!(
+                        "Unable to migrate node '{}' of flow '{}' to node library {}.",
+                        node, self.name, migration.version
+                    )
+                })?;
+                applied.push(AppliedMigration {
+                    node,
+                    kind: kind.into(),
+                    version: migration.version.clone(),
+                    description: migration.description.clone(),
+                });
+            }
+        }
+        migrated.library = Some(supported.clone());
+
+        *self = migrated;
+        Ok(MigrationReport {
+            flow: self.name.clone(),
+            library: supported.clone(),
+            applied,
+        })
+    }
+
+    /// A copy of the definition with the migrations of `registry` applied, see [`Self::migrate`].
+    pub(crate) fn migrated(&self, registry: &NodeRegistry) -> Result<Self> {
+        let mut migrated = self.clone();
+        migrated.migrate(registry)?;
+        Ok(migrated)
+    }
+}
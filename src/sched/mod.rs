@@ -1,4 +1,13 @@
+pub mod checkpoint;
+pub mod definition;
+pub mod editor;
 pub mod executor;
 pub mod flow;
+pub mod metrics;
+pub mod migration;
+pub mod registry;
+pub mod recording;
+mod render;
 pub mod scheduler;
+pub mod validation;
 pub mod version;
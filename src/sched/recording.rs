@@ -0,0 +1,246 @@
+use anyhow::{anyhow, Context as AnyhowContext, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use crate::{
+    connection::Input,
+    node::{Context, State},
+    sched::{
+        definition::node_id,
+        flow::{Flow, NodeId, PortKind},
+    },
+};
+
+/// A value that was sent to an input, as written by a [`TrafficRecorder`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrafficRecord {
+    pub epoch: u64,
+    pub timestamp: SystemTime,
+    /// The node the value was sent to.
+    pub node: String,
+    /// The index of the input the value was sent to.
+    pub port: usize,
+    pub value: Value,
+}
+
+struct Sink {
+    writer: Box<dyn Write + Send>,
+    error: Option<io::Error>,
+}
+
+/// Writes the values sent to selected inputs of a flow as JSON lines of [`TrafficRecord`]s.
+///
+/// Clones share the writer. Write errors are kept and reported by [`TrafficRecorder::flush`].
+#[derive(Clone)]
+pub struct TrafficRecorder {
+    sink: Arc<Mutex<Sink>>,
+    context: State<Context>,
+}
+
+impl TrafficRecorder {
+    /// Creates a recorder that takes the epoch of each record from `context`.
+    pub fn new<W>(writer: W, context: State<Context>) -> Self
+    where
+        W: Write + Send + 'static,
+    {
+        Self {
+            sink: Arc::new(Mutex::new(Sink {
+                writer: Box::new(writer),
+                error: None,
+            })),
+            context,
+        }
+    }
+
+    /// Creates a recorder writing to the file at `path`.
+    pub fn create<P: AsRef<Path>>(path: P, context: State<Context>) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?), context))
+    }
+
+    /// Flushes the writer and returns the first error that occurred while recording.
+    pub fn flush(&self) -> io::Result<()> {
+        let mut sink = self.sink.lock().unwrap();
+        match sink.error.take() {
+            Some(err) => Err(err),
+            None => sink.writer.flush(),
+        }
+    }
+
+    /// Encodes `value` right away, since it is moved to the input once it is sent.
+    fn encode<T: Serialize>(
+        &self,
+        node: &str,
+        port: usize,
+        value: &T,
+    ) -> serde_json::Result<TrafficRecord> {
+        let epoch = self.context.0.lock().unwrap().epoch();
+        serde_json::to_value(value).map(|value| TrafficRecord {
+            epoch,
+            timestamp: SystemTime::now(),
+            node: node.into(),
+            port,
+            value,
+        })
+    }
+
+    fn write(&self, record: serde_json::Result<TrafficRecord>) {
+        let mut sink = self.sink.lock().unwrap();
+        if sink.error.is_some() {
+            return;
+        }
+        let res = record
+            .and_then(|record| serde_json::to_writer(&mut sink.writer, &record))
+            .map_err(io::Error::from)
+            .and_then(|_| sink.writer.write_all(b"\n"));
+        if let Err(err) = res {
+            sink.error = Some(err);
+        }
+    }
+}
+
+impl Flow {
+    /// Records all values that outputs send to the input `in_port` of `in_node`, replacing a
+    /// previous recording of that input.
+    pub fn record<T>(
+        &self,
+        in_node: NodeId,
+        in_port: usize,
+        recorder: &TrafficRecorder,
+    ) -> Result<()>
+    where
+        T: Serialize + 'static,
+    {
+        let input: Input<T> = self.port(in_node, PortKind::Input, in_port)?;
+        let node = self.handle(in_node).unwrap().name().to_string();
+        let recorder = recorder.clone();
+        input.set_tap(move |value| {
+            let record = recorder.encode(&node, in_port, value);
+            let recorder = recorder.clone();
+            move || recorder.write(record)
+        });
+        Ok(())
+    }
+
+    /// Stops recording the input `in_port` of `in_node`.
+    pub fn stop_recording<T>(&self, in_node: NodeId, in_port: usize) -> Result<()>
+    where
+        T: 'static,
+    {
+        let input: Input<T> = self.port(in_node, PortKind::Input, in_port)?;
+        input.clear_tap();
+        Ok(())
+    }
+}
+
+type FeedFn = Box<dyn Fn(Value) -> Result<()>>;
+
+/// Feeds recorded traffic into the inputs of a flow, e.g. to debug a single node against real
+/// data.
+///
+/// Only records of inputs that were bound with [`TrafficReplay::bind`] are replayed.
+pub struct TrafficReplay {
+    records: Vec<TrafficRecord>,
+    inputs: HashMap<(String, usize), FeedFn>,
+}
+
+impl TrafficReplay {
+    pub fn new(records: Vec<TrafficRecord>) -> Self {
+        Self {
+            records,
+            inputs: HashMap::new(),
+        }
+    }
+
+    /// Reads the JSON lines written by a [`TrafficRecorder`].
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Self> {
+        let mut records = Vec::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line.context("Unable to read recorded traffic.")?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            records.push(
+                serde_json::from_str(&line)
+                    .with_context(|| format!("Unable to parse record in line {}.", index + 1))?,
+            );
+        }
+        Ok(Self::new(records))
+    }
+
+    /// Reads the records of a file written by a [`TrafficRecorder`].
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = File::open(path.as_ref()).with_context(|| {
+            format!(
+                "Unable to open recorded traffic '{}'.",
+                path.as_ref().display()
+            )
+        })?;
+        Self::from_reader(BufReader::new(file))
+    }
+
+    pub fn records(&self) -> &[TrafficRecord] {
+        &self.records
+    }
+
+    /// Feeds records of the input `port` of the recorded node `node` into `input`.
+    pub fn bind<T>(&mut self, node: &str, port: usize, input: Input<T>)
+    where
+        T: DeserializeOwned + 'static,
+    {
+        let feed = move |value| {
+            let value = serde_json::from_value(value)?;
+            input
+                .send(value)
+                .map_err(|_| anyhow!("Unable to send the recorded value."))
+        };
+        self.inputs.insert((node.into(), port), Box::new(feed));
+    }
+
+    /// Feeds records of the input `port` of `node` into the same input of `flow`.
+    pub fn bind_flow<T>(&mut self, flow: &Flow, node: &str, port: usize) -> Result<()>
+    where
+        T: DeserializeOwned + 'static,
+    {
+        let id = node_id(flow, node)?;
+        let input: Input<T> = flow.port(id, PortKind::Input, port)?;
+        self.bind(node, port, input);
+        Ok(())
+    }
+
+    /// Feeds all records of bound inputs in recorded order and returns how many were fed.
+    pub fn feed(&self) -> Result<usize> {
+        self.feed_where(|_| true)
+    }
+
+    /// Like [`TrafficReplay::feed`], but only feeds the records of a single epoch.
+    pub fn feed_epoch(&self, epoch: u64) -> Result<usize> {
+        self.feed_where(|record| record.epoch == epoch)
+    }
+
+    fn feed_where<P>(&self, predicate: P) -> Result<usize>
+    where
+        P: Fn(&TrafficRecord) -> bool,
+    {
+        let mut fed = 0;
+        for record in self.records.iter().filter(|record| predicate(record)) {
+            if let Some(feed) = self.inputs.get(&(record.node.clone(), record.port)) {
+                feed(record.value.clone()).with_context(|| {
+                    format!(
+                        "Unable to replay a value to input {} of node '{}'.",
+                        record.port, record.node
+                    )
+                })?;
+                fed += 1;
+            }
+        }
+        Ok(fed)
+    }
+}
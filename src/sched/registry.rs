@@ -0,0 +1,293 @@
+use anyhow::{Context as AnyhowContext, Result};
+use jsonschema::JSONSchema;
+use schemars::schema_for;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use crate::{
+    connection::RuntimeConnectable,
+    node::{Context, FromProperties, Node, State},
+    sched::{
+        definition::{DefinitionError, FlowDefinition},
+        flow::{Flow, FlowError, NodeId},
+        migration::{Migration, MigrationReport},
+        version::Version,
+    },
+    subflow::SubflowNode,
+};
+
+type ConstructFn = dyn Fn(&mut Flow, &str, State<Context>, Value) -> Result<NodeId> + Send + Sync;
+type ConnectFn = fn(&mut Flow, NodeId, usize, NodeId, usize) -> Result<(), FlowError>;
+
+/// The JSON Schema of the props of a node kind, see [`FromProperties`].
+pub(crate) struct PropsSchema {
+    schema: Value,
+    validator: JSONSchema,
+}
+
+impl PropsSchema {
+    /// Validates the props of the node `name`, missing props are treated as an empty object.
+    pub(crate) fn validate(&self, name: &str, props: &Value) -> Result<(), DefinitionError> {
+        let props = or_empty(props.clone());
+        let Err(mut errors) = self.validator.validate(&props) else {
+            return Ok(());
+        };
+        match errors.next() {
+            Some(err) => {
+                let field = err.instance_path.to_string();
+                Err(DefinitionError::InvalidProps {
+                    node: name.into(),
+                    field: if field.is_empty() { "/".into() } else { field },
+                    message: err.to_string(),
+                })
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Maps the node kinds and element types used in a [`FlowDefinition`] to Rust types.
+pub struct NodeRegistry {
+    kinds: HashMap<String, Arc<ConstructFn>>,
+    schemas: HashMap<String, Arc<PropsSchema>>,
+    subflows: HashMap<String, FlowDefinition>,
+    types: HashMap<String, ConnectFn>,
+    library: Version,
+    /// Migrations with the kind they are registered for, ordered by version.
+    migrations: Vec<(String, Migration)>,
+}
+
+impl Default for NodeRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NodeRegistry {
+    /// Creates a registry that knows the primitive element types and `String`.
+    pub fn new() -> Self {
+        let mut registry = Self {
+            kinds: HashMap::new(),
+            schemas: HashMap::new(),
+            subflows: HashMap::new(),
+            types: HashMap::new(),
+            library: Version::new(0, 0, 0),
+            migrations: Vec::new(),
+        };
+        registry.register_type::<bool>("bool");
+        registry.register_type::<i8>("i8");
+        registry.register_type::<i16>("i16");
+        registry.register_type::<i32>("i32");
+        registry.register_type::<i64>("i64");
+        registry.register_type::<u8>("u8");
+        registry.register_type::<u16>("u16");
+        registry.register_type::<u32>("u32");
+        registry.register_type::<u64>("u64");
+        registry.register_type::<usize>("usize");
+        registry.register_type::<f32>("f32");
+        registry.register_type::<f64>("f64");
+        registry.register_type::<String>("String");
+        registry
+    }
+
+    /// Registers a constructor for nodes of the given kind. The constructor receives the node
+    /// name, the context and the props of the node definition.
+    pub fn register_node<T, F>(&mut self, kind: &str, constructor: F)
+    where
+        T: Node + RuntimeConnectable,
+        F: Fn(&str, State<Context>, Value) -> Result<T> + Send + Sync + 'static,
+    {
+        self.kinds.insert(
+            kind.into(),
+            Arc::new(move |flow, name, context, props| {
+                let node = constructor(name, context, props)?;
+                Ok(flow.add_node(node)?)
+            }),
+        );
+    }
+
+    /// Registers nodes of the given kind that are constructed from typed properties. Missing
+    /// props are treated as an empty object.
+    pub fn register_typed_node<T>(&mut self, kind: &str)
+    where
+        T: FromProperties + RuntimeConnectable,
+    {
+        let schema = serde_json::to_value(schema_for!(T::Properties))
+            .expect("A generated schema is serializable.");
+        let validator = JSONSchema::compile(&schema).expect("A generated schema is valid.");
+        self.schemas
+            .insert(kind.into(), Arc::new(PropsSchema { schema, validator }));
+
+        self.register_node(kind, |name, context, props| {
+            let props = serde_json::from_value(or_empty(props)).map_err(|err| {
+                DefinitionError::InvalidProps {
+                    node: name.into(),
+                    field: "/".into(),
+                    message: err.to_string(),
+                }
+            })?;
+            T::from_properties(name, context, props)
+        });
+    }
+
+    /// The JSON Schema of the props of nodes of the given kind, if they were registered with
+    /// [`NodeRegistry::register_typed_node`].
+    pub fn schema(&self, kind: &str) -> Option<&Value> {
+        self.schemas.get(kind).map(|schema| &schema.schema)
+    }
+
+    /// Validates the props of the node `name` against the schema of its kind. Kinds without a
+    /// schema accept any props.
+    pub fn validate_props(
+        &self,
+        name: &str,
+        kind: &str,
+        props: &Value,
+    ) -> Result<(), DefinitionError> {
+        match self.schemas.get(kind) {
+            Some(schema) => schema.validate(name, props),
+            None => Ok(()),
+        }
+    }
+
+    /// Registers a flow definition that can be used as a node of the given kind, see
+    /// [`SubflowNode`].
+    ///
+    /// Returns the migrations the registry currently applies to the definition. The definition
+    /// is kept as given and migrated whenever the subflow is built, so that migrations registered
+    /// later are applied as well.
+    pub fn register_subflow(
+        &mut self,
+        kind: &str,
+        definition: FlowDefinition,
+    ) -> Result<MigrationReport> {
+        let report = definition.clone().migrate(self)?;
+        self.subflows.insert(kind.into(), definition);
+        Ok(report)
+    }
+
+    /// Registers an element type that edges of a definition can refer to by `name`. Elements
+    /// queued on such edges are included in checkpoints.
+    pub fn register_type<T>(&mut self, name: &str)
+    where
+        T: Serialize + DeserializeOwned + Send + 'static,
+    {
+        self.types.insert(name.into(), Flow::connect_serializable::<T>);
+    }
+
+    /// Registers an element type that can not be serialized. Checkpoints of flows fail while
+    /// elements are queued on edges of this type.
+    pub fn register_opaque_type<T>(&mut self, name: &str)
+    where
+        T: Send + 'static,
+    {
+        self.types.insert(name.into(), Flow::connect::<T>);
+    }
+
+    /// The version of the registered node kinds, `0.0.0` unless set. Definitions record the
+    /// version they were written for and are migrated to this one, see
+    /// [`FlowDefinition::migrate`].
+    pub fn library_version(&self) -> &Version {
+        &self.library
+    }
+
+    pub fn set_library_version(&mut self, version: Version) {
+        self.library = version;
+    }
+
+    /// Registers a migration for nodes of the given kind, see [`FlowDefinition::migrate`].
+    /// Migrations of the same version are applied in the order they were registered.
+    pub fn register_migration(&mut self, kind: &str, migration: Migration) {
+        self.migrations.push((kind.into(), migration));
+        self.migrations
+            .sort_by(|(_, lhs), (_, rhs)| lhs.version().cmp(rhs.version()));
+    }
+
+    /// The registered migrations with their kinds, ordered by version.
+    pub(crate) fn migrations(&self) -> impl Iterator<Item = (&str, &Migration)> {
+        self.migrations
+            .iter()
+            .map(|(kind, migration)| (kind.as_str(), migration))
+    }
+
+    /// Constructs a node of the given kind and adds it to `flow`.
+    pub fn add_node(
+        &self,
+        flow: &mut Flow,
+        kind: &str,
+        name: &str,
+        context: State<Context>,
+        props: Value,
+    ) -> Result<NodeId> {
+        if let Some(construct) = self.kinds.get(kind) {
+            let id = construct(flow, name, context, props.clone()).with_context(|| {
+                format!("Unable to construct node '{}' of kind '{}'.", name, kind)
+            })?;
+            let handle = flow.handle(id).unwrap();
+            handle.set_config(props);
+            handle.set_schema(self.schemas.get(kind).cloned());
+            return Ok(id);
+        }
+        if let Some(definition) = self.subflows.get(kind) {
+            self.check_subflow_cycles(kind, &mut Vec::new(), &mut HashSet::new())?;
+            let node = SubflowNode::from_definition(name, definition, self, context).with_context(
+                || format!("Unable to construct subflow '{}' of kind '{}'.", name, kind),
+            )?;
+            return Ok(flow.add_node(node)?);
+        }
+        Err(DefinitionError::UnknownKind(kind.into()).into())
+    }
+
+    /// Fails if expanding the subflow `kind` would expand a subflow kind within itself, which
+    /// would recurse forever. `expanding` holds the kinds on the path to `kind`.
+    fn check_subflow_cycles<'a>(
+        &'a self,
+        kind: &'a str,
+        expanding: &mut Vec<&'a str>,
+        checked: &mut HashSet<&'a str>,
+    ) -> Result<(), DefinitionError> {
+        if expanding.contains(&kind) {
+            return Err(DefinitionError::RecursiveSubflow(kind.into()));
+        }
+        let Some(definition) = self.subflows.get(kind) else {
+            return Ok(());
+        };
+        if !checked.insert(kind) {
+            return Ok(());
+        }
+        expanding.push(kind);
+        for node in &definition.nodes {
+            self.check_subflow_cycles(&node.kind, expanding, checked)?;
+        }
+        expanding.pop();
+        Ok(())
+    }
+
+    /// Connects two ports of `flow` with the element type registered as `type_name`.
+    pub fn connect(
+        &self,
+        flow: &mut Flow,
+        type_name: &str,
+        out_node: NodeId,
+        out_port: usize,
+        in_node: NodeId,
+        in_port: usize,
+    ) -> Result<()> {
+        let connect = self
+            .types
+            .get(type_name)
+            .ok_or_else(|| DefinitionError::UnknownType(type_name.into()))?;
+        Ok(connect(flow, out_node, out_port, in_node, in_port)?)
+    }
+}
+
+fn or_empty(props: Value) -> Value {
+    match props {
+        Value::Null => Value::Object(Default::default()),
+        props => props,
+    }
+}
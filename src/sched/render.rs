@@ -0,0 +1,121 @@
+use std::fmt::Write;
+
+use crate::sched::flow::{Flow, FlowEdge, NodeHandle, NodeId};
+
+impl Flow {
+    /// Renders the flow as a Graphviz DOT digraph.
+    ///
+    /// Nodes are labeled with their name, type and lifecycle state, edges with their port names,
+    /// element type and current queue depth.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        let _ = writeln!(dot, "digraph \"{}\" {{", escape_dot(self.name()));
+        let _ = writeln!(dot, "    node [shape=box];");
+        for handle in self.iter() {
+            let _ = writeln!(
+                dot,
+                "    {} [label=\"{}\"];",
+                node_key(handle.id()),
+                escape_dot(&node_label(handle)).replace('\n', "\\n")
+            );
+        }
+        for edge in self.edges() {
+            let _ = writeln!(
+                dot,
+                "    {} -> {} [label=\"{}\"];",
+                node_key(edge.from().node),
+                node_key(edge.to().node),
+                escape_dot(&self.edge_label(edge)).replace('\n', "\\n")
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Renders the flow as a Mermaid flowchart, with the same labels as [`Flow::to_dot`].
+    pub fn to_mermaid(&self) -> String {
+        let mut mermaid = String::from("flowchart LR\n");
+        for handle in self.iter() {
+            let _ = writeln!(
+                mermaid,
+                "    {}[\"{}\"]",
+                node_key(handle.id()),
+                escape_mermaid(&node_label(handle)).replace('\n', "<br/>")
+            );
+        }
+        for edge in self.edges() {
+            let _ = writeln!(
+                mermaid,
+                "    {} -->|\"{}\"| {}",
+                node_key(edge.from().node),
+                escape_mermaid(&self.edge_label(edge)).replace('\n', "<br/>"),
+                node_key(edge.to().node)
+            );
+        }
+        mermaid
+    }
+
+    fn edge_label(&self, edge: &FlowEdge) -> String {
+        let port_name = |id: NodeId, index: usize, output: bool| -> String {
+            self.handle(id)
+                .map(|h| {
+                    let ports = h.ports();
+                    let ports = ports.lock().unwrap();
+                    match output {
+                        true if index < ports.output_len() => ports.output_name_at(index).into(),
+                        false if index < ports.input_len() => ports.input_name_at(index).into(),
+                        _ => "?".into(),
+                    }
+                })
+                .unwrap_or_else(|| "?".into())
+        };
+        format!(
+            "{} -> {}\n{} ({} queued)",
+            port_name(edge.from().node, edge.from().index, true),
+            port_name(edge.to().node, edge.to().index, false),
+            short_type_name(edge.type_name()),
+            edge.queue_depth()
+        )
+    }
+}
+
+fn node_key(id: NodeId) -> String {
+    format!("n{}", id.0)
+}
+
+fn node_label(handle: &NodeHandle) -> String {
+    format!(
+        "{}\n{}\n{}",
+        handle.name(),
+        short_type_name(handle.type_name()),
+        handle.state()
+    )
+}
+
+/// Strips module paths from a type name, e.g. `alloc::vec::Vec<my::Type>` becomes `Vec<Type>`.
+fn short_type_name(name: &str) -> String {
+    let mut short = String::new();
+    let mut segment = String::new();
+    for c in name.chars() {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            segment.push(c);
+        } else {
+            short.push_str(segment.rsplit("::").next().unwrap_or_default());
+            segment.clear();
+            short.push(c);
+        }
+    }
+    short.push_str(segment.rsplit("::").next().unwrap_or_default());
+    short
+}
+
+fn escape_dot(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(label: &str) -> String {
+    label
+        .replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
@@ -12,6 +12,7 @@ pub trait Scheduler {
     fn restart_epoch(&mut self);
 }
 
+#[derive(Default)]
 pub struct RoundRobinScheduler {
     cur_node_idx: usize,
 }
@@ -23,10 +24,10 @@ impl RoundRobinScheduler {
 }
 
 impl Scheduler for RoundRobinScheduler {
-    fn get_next_node_idx(&mut self, info: &SchedulingInfo) -> usize {
+    fn get_next_node_idx(&mut self, _info: &SchedulingInfo) -> usize {
         self.cur_node_idx += 1;
 
-        return self.cur_node_idx - 1;
+        self.cur_node_idx - 1
     }
 
     fn epoch_is_over(&self, info: &SchedulingInfo) -> bool {
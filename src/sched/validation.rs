@@ -0,0 +1,218 @@
+use std::{
+    any::TypeId,
+    collections::{BTreeSet, HashMap, VecDeque},
+    fmt,
+};
+
+use crate::{
+    connection::RuntimeConnectable,
+    sched::flow::{Flow, NodeId, Port},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The flow can be executed, but probably does not behave as intended.
+    Warning,
+    /// The flow must not be executed.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationIssue {
+    /// Sending to this output fails, since no successor is connected, and it is not declared
+    /// optional.
+    UnconnectedOutput { node: String, index: usize },
+    /// No edge of the flow ends at this input. It may still be fed from outside the flow.
+    UnconnectedInput { node: String, index: usize },
+    /// The ports of an edge do not carry the element type the edge was connected with.
+    TypeMismatch {
+        from: Port,
+        to: Port,
+        type_name: &'static str,
+    },
+    /// Several nodes report the same name.
+    DuplicateName(String),
+    /// The node can not be reached from any source of the flow.
+    Unreachable { node: String },
+}
+
+impl ValidationIssue {
+    pub fn severity(&self) -> Severity {
+        match self {
+            ValidationIssue::UnconnectedOutput { .. }
+            | ValidationIssue::TypeMismatch { .. }
+            | ValidationIssue::DuplicateName(_) => Severity::Error,
+            ValidationIssue::UnconnectedInput { .. } | ValidationIssue::Unreachable { .. } => {
+                Severity::Warning
+            }
+        }
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValidationIssue::UnconnectedOutput { node, index } => {
+                write!(f, "Output {} of node '{}' is not connected.", index, node)
+            }
+            ValidationIssue::UnconnectedInput { node, index } => {
+                write!(f, "Input {} of node '{}' is not connected.", index, node)
+            }
+            ValidationIssue::TypeMismatch {
+                from,
+                to,
+                type_name,
+            } => write!(
+                f,
+                "Edge from output {} of node {} to input {} of node {} does not match its type {}.",
+                from.index, from.node, to.index, to.node, type_name
+            ),
+            ValidationIssue::DuplicateName(name) => {
+                write!(f, "Node name '{}' is used more than once.", name)
+            }
+            ValidationIssue::Unreachable { node } => {
+                write!(f, "Node '{}' is not reachable from any source.", node)
+            }
+        }
+    }
+}
+
+/// The outcome of [`Flow::validate`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    pub fn issues(&self) -> &[ValidationIssue] {
+        &self.issues
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|i| i.severity() == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|i| i.severity() == Severity::Warning)
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for issue in &self.issues {
+            writeln!(f, "{}: {}", issue.severity(), issue)?;
+        }
+        Ok(())
+    }
+}
+
+impl Flow {
+    /// Checks the flow for problems that would surface at runtime.
+    ///
+    /// Outputs count as connected if they are wired inside or outside of the flow, inputs only if
+    /// an edge of the flow ends at them.
+    pub fn validate(&self) -> ValidationReport {
+        let mut issues = Vec::new();
+
+        let mut names: HashMap<String, usize> = HashMap::new();
+        for handle in self.iter() {
+            let name = handle.node().lock().unwrap().name().to_string();
+            *names.entry(name).or_default() += 1;
+        }
+        let mut duplicates: Vec<String> = names
+            .into_iter()
+            .filter(|(_, count)| *count > 1)
+            .map(|(name, _)| name)
+            .collect();
+        duplicates.sort();
+        issues.extend(duplicates.into_iter().map(ValidationIssue::DuplicateName));
+
+        for handle in self.iter() {
+            let ports = handle.ports();
+            let ports = ports.lock().unwrap();
+            for index in 0..ports.output_len() {
+                let output = ports.dyn_output_at(index);
+                if !output.is_connected() && !ports.output_is_optional(index) {
+                    issues.push(ValidationIssue::UnconnectedOutput {
+                        node: handle.name().into(),
+                        index,
+                    });
+                }
+            }
+            let connected: BTreeSet<usize> =
+                self.incoming(handle.id()).map(|e| e.to().index).collect();
+            for index in (0..ports.input_len()).filter(|i| !connected.contains(i)) {
+                issues.push(ValidationIssue::UnconnectedInput {
+                    node: handle.name().into(),
+                    index,
+                });
+            }
+        }
+
+        for edge in self.edges() {
+            let output_type = self.port_type(edge.from(), |p, i| {
+                (i < p.output_len()).then(|| (*p.output_at(i)).type_id())
+            });
+            let input_type = self.port_type(edge.to(), |p, i| {
+                (i < p.input_len()).then(|| (*p.input_at(i)).type_id())
+            });
+            if output_type != Some(edge.output_type) || input_type != Some(edge.input_type) {
+                issues.push(ValidationIssue::TypeMismatch {
+                    from: edge.from(),
+                    to: edge.to(),
+                    type_name: edge.type_name(),
+                });
+            }
+        }
+
+        let reachable = self.reachable_from_sources();
+        for handle in self.iter().filter(|h| !reachable.contains(&h.id())) {
+            issues.push(ValidationIssue::Unreachable {
+                node: handle.name().into(),
+            });
+        }
+
+        ValidationReport { issues }
+    }
+
+    fn port_type<F>(&self, port: Port, get: F) -> Option<TypeId>
+    where
+        F: FnOnce(&dyn RuntimeConnectable, usize) -> Option<TypeId>,
+    {
+        let ports = self.handle(port.node)?.ports();
+        let ports = ports.lock().unwrap();
+        get(&*ports, port.index)
+    }
+
+    fn reachable_from_sources(&self) -> BTreeSet<NodeId> {
+        let mut reachable: BTreeSet<NodeId> = BTreeSet::new();
+        let mut queue: VecDeque<NodeId> = self.sources().into();
+        while let Some(id) = queue.pop_front() {
+            if reachable.insert(id) {
+                queue.extend(self.successors(id));
+            }
+        }
+        reachable
+    }
+}
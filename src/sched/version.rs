@@ -1,8 +1,24 @@
-#[derive(Debug)]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{cmp::Ordering, fmt, str::FromStr};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("'{version}' is not a valid version: {reason}.")]
+pub struct VersionError {
+    pub version: String,
+    pub reason: String,
+}
+
+/// A semantic version like `1.2.3` or `1.2.3-beta.1`.
+///
+/// Versions are ordered by semver precedence, i.e. a pre-release orders before its release.
+/// They are (de)serialized as strings.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Version {
     major: u32,
     minor: u32,
     patch: u32,
+    pre: Option<String>,
 }
 
 impl Version {
@@ -11,6 +27,169 @@ impl Version {
             major,
             minor,
             patch,
+            pre: None,
+        }
+    }
+
+    /// The version of this crate, against which definitions declare the runtime they need.
+    pub fn runtime() -> Self {
+        env!("CARGO_PKG_VERSION")
+            .parse()
+            .expect("The crate version is a valid version.")
+    }
+
+    /// Returns the version with the given pre-release tag, e.g. `alpha.1`.
+    pub fn with_pre(mut self, pre: &str) -> Result<Self, VersionError> {
+        check_pre(pre).map_err(|reason| VersionError {
+            version: format!("{}-{}", self, pre),
+            reason,
+        })?;
+        self.pre = Some(pre.into());
+        Ok(self)
+    }
+
+    pub fn major(&self) -> u32 {
+        self.major
+    }
+
+    pub fn minor(&self) -> u32 {
+        self.minor
+    }
+
+    pub fn patch(&self) -> u32 {
+        self.patch
+    }
+
+    pub fn pre(&self) -> Option<&str> {
+        self.pre.as_deref()
+    }
+
+    /// Returns whether `self` can be used where `required` is expected.
+    ///
+    /// That is the case if `self` is not older than `required` and both share the major version.
+    /// Before `1.0.0`, the minor version has to match as well.
+    pub fn is_compatible_with(&self, required: &Version) -> bool {
+        self.major == required.major
+            && (self.major != 0 || self.minor == required.minor)
+            && self >= required
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = &self.pre {
+            write!(f, "-{}", pre)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Version {
+    type Err = VersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = |reason: &str| VersionError {
+            version: s.into(),
+            reason: reason.into(),
+        };
+        let (release, pre) = match s.split_once('-') {
+            Some((release, pre)) => (release, Some(pre)),
+            None => (s, None),
+        };
+
+        let parts = release
+            .split('.')
+            .map(|part| parse_number(part).ok_or_else(|| err("expected MAJOR.MINOR.PATCH")))
+            .collect::<Result<Vec<_>, _>>()?;
+        let [major, minor, patch] = parts[..] else {
+            return Err(err("expected MAJOR.MINOR.PATCH"));
+        };
+
+        let version = Version::new(major, minor, patch);
+        match pre {
+            Some(pre) => version.with_pre(pre).map_err(|e| err(&e.reason)),
+            None => Ok(version),
+        }
+    }
+}
+
+/// Parses a numeric identifier, which must not have leading zeros.
+fn parse_number(s: &str) -> Option<u32> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) || (s.len() > 1 && s.starts_with('0'))
+    {
+        return None;
+    }
+    s.parse().ok()
+}
+
+fn check_pre(pre: &str) -> Result<(), String> {
+    for identifier in pre.split('.') {
+        if identifier.is_empty()
+            || !identifier
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        {
+            return Err(format!("invalid pre-release identifier '{}'", identifier));
+        }
+        if identifier.bytes().all(|b| b.is_ascii_digit()) && parse_number(identifier).is_none() {
+            return Err(format!("invalid numeric identifier '{}'", identifier));
+        }
+    }
+    Ok(())
+}
+
+/// Compares pre-release tags by semver precedence. Numeric identifiers compare numerically and
+/// order before alphanumeric ones.
+fn compare_pre(lhs: &str, rhs: &str) -> Ordering {
+    let mut lhs = lhs.split('.');
+    let mut rhs = rhs.split('.');
+    loop {
+        let ordering = match (lhs.next(), rhs.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(l), Some(r)) => match (parse_number(l), parse_number(r)) {
+                (Some(l), Some(r)) => l.cmp(&r),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => l.cmp(r),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
         }
     }
 }
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(lhs), Some(rhs)) => compare_pre(lhs, rhs),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Serialize for Version {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,302 @@
+//! Utilities to test nodes and flows without an executor.
+//!
+//! A [`FlowHarness`] updates the nodes of a flow on the calling thread, in the order they were
+//! added, until no element is queued anymore. [`Feeder`]s send elements to inputs and
+//! [`Probe`]s collect everything sent by outputs.
+
+use anyhow::{anyhow, Context as AnyhowContext, Result};
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+
+use crate::{
+    connection::{connect, Input, Output},
+    definition::node_id,
+    flow::{Flow, FlowError, PortKind},
+    node::{Context, State},
+};
+
+#[cfg(feature = "proptest")]
+pub mod proptest;
+
+/// The default of [`FlowHarness::set_max_epochs`].
+pub const DEFAULT_MAX_EPOCHS: u64 = 1000;
+
+#[derive(Debug, Error)]
+pub enum HarnessError {
+    #[error("Flow was still busy after {epochs} epochs.")]
+    NotIdle { epochs: u64 },
+}
+
+/// Collects all elements sent to its input.
+///
+/// Clones share the collected elements.
+pub struct Probe<T> {
+    input: Input<T>,
+    received: Arc<Mutex<Vec<T>>>,
+}
+
+impl<T> Clone for Probe<T> {
+    fn clone(&self) -> Self {
+        Self {
+            input: self.input.clone(),
+            received: self.received.clone(),
+        }
+    }
+}
+
+impl<T> Default for Probe<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Probe<T> {
+    pub fn new() -> Self {
+        Self {
+            input: Input::new(),
+            received: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// The input to connect outputs to.
+    pub fn input(&self) -> Input<T> {
+        self.input.clone()
+    }
+
+    /// Removes and returns all elements received so far.
+    pub fn take(&self) -> Vec<T> {
+        let mut received = self.received.lock().unwrap();
+        self.collect(&mut received);
+        std::mem::take(&mut *received)
+    }
+
+    pub fn len(&self) -> usize {
+        let mut received = self.received.lock().unwrap();
+        self.collect(&mut received);
+        received.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn collect(&self, received: &mut Vec<T>) {
+        while let Ok(elem) = self.input.next_elem() {
+            received.push(elem);
+        }
+    }
+}
+
+impl<T: Clone> Probe<T> {
+    /// All elements received so far, oldest first.
+    pub fn values(&self) -> Vec<T> {
+        let mut received = self.received.lock().unwrap();
+        self.collect(&mut received);
+        received.clone()
+    }
+
+    pub fn last(&self) -> Option<T> {
+        self.values().pop()
+    }
+}
+
+/// Sends elements to an input through an [`Output`], just like a predecessor node would.
+pub struct Feeder<T> {
+    output: Output<T>,
+}
+
+impl<T> Clone for Feeder<T> {
+    fn clone(&self) -> Self {
+        Self {
+            output: self.output.clone(),
+        }
+    }
+}
+
+impl<T> Feeder<T> {
+    pub fn new(input: Input<T>, context: State<Context>) -> Self {
+        let output = Output::new(context);
+        connect(output.clone(), input);
+        Self { output }
+    }
+
+    pub fn send(&self, elem: T) {
+        // The output is always connected, and inputs never close their queue.
+        let _ = self.output.clone().send(elem);
+    }
+
+    pub fn send_all<I>(&self, elems: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for elem in elems {
+            self.send(elem);
+        }
+    }
+}
+
+/// A failed node update, see [`FlowHarness::errors`].
+#[derive(Debug)]
+pub struct UpdateFailure {
+    pub epoch: u64,
+    pub node: String,
+    pub error: anyhow::Error,
+}
+
+/// Runs a flow deterministically on the calling thread.
+///
+/// Each epoch updates every node once, in the order they were added. Failed updates are recorded
+/// instead of stopping the run, like the executor does.
+pub struct FlowHarness {
+    flow: Flow,
+    context: State<Context>,
+    epoch: u64,
+    max_epochs: u64,
+    started: bool,
+    errors: Vec<UpdateFailure>,
+}
+
+impl FlowHarness {
+    /// Creates a harness for `flow`, whose nodes were created with `context`.
+    pub fn new(flow: Flow, context: State<Context>) -> Self {
+        Self {
+            flow,
+            context,
+            epoch: 0,
+            max_epochs: DEFAULT_MAX_EPOCHS,
+            started: false,
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn flow(&self) -> &Flow {
+        &self.flow
+    }
+
+    pub fn context(&self) -> State<Context> {
+        self.context.clone()
+    }
+
+    /// The number of epochs run so far.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Limits how many epochs [`FlowHarness::run_until_idle`] runs before giving up.
+    pub fn set_max_epochs(&mut self, max_epochs: u64) {
+        self.max_epochs = max_epochs;
+    }
+
+    /// Creates a feeder for the input `port` of the node `node`.
+    pub fn feeder<T: 'static>(&self, node: &str, port: usize) -> Result<Feeder<T>> {
+        let id = node_id(&self.flow, node)?;
+        let input: Input<T> = self.flow.port(id, PortKind::Input, port)?;
+        Ok(Feeder::new(input, self.context.clone()))
+    }
+
+    /// Connects the output `port` of the node `node` to a new probe.
+    pub fn probe<T: 'static>(&self, node: &str, port: usize) -> Result<Probe<T>> {
+        let id = node_id(&self.flow, node)?;
+        let output: Output<T> = self.flow.port(id, PortKind::Output, port)?;
+        if output.is_connected() {
+            return Err(FlowError::AlreadyConnected {
+                node: node.into(),
+                index: port,
+            }
+            .into());
+        }
+        let probe = Probe::new();
+        connect(output, probe.input());
+        Ok(probe)
+    }
+
+    /// Validates the flow, then inits and readies all nodes. Called by the first run.
+    pub fn start(&mut self) -> Result<()> {
+        if self.started {
+            return Ok(());
+        }
+        let report = self.flow.validate();
+        if report.has_errors() {
+            return Err(FlowError::Invalid(report).into());
+        }
+        self.flow.init_all().context("Unable to init all nodes.")?;
+        self.flow
+            .ready_all()
+            .context("Unable to make all nodes ready.")?;
+        self.started = true;
+        Ok(())
+    }
+
+    /// Updates every node once.
+    pub fn run_epoch(&mut self) -> Result<()> {
+        self.start()?;
+        self.epoch += 1;
+        self.context.0.lock().unwrap().set_epoch(self.epoch);
+        for handle in self.flow.iter() {
+            if let Err(error) = handle.update() {
+                self.errors.push(UpdateFailure {
+                    epoch: self.epoch,
+                    node: handle.name().into(),
+                    error,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs epochs until no input of the flow has queued elements, at least one. Returns the
+    /// number of epochs run.
+    pub fn run_until_idle(&mut self) -> Result<u64> {
+        for epochs in 1..=self.max_epochs {
+            self.run_epoch()?;
+            if self.is_idle() {
+                return Ok(epochs);
+            }
+        }
+        Err(HarnessError::NotIdle {
+            epochs: self.max_epochs,
+        }
+        .into())
+    }
+
+    /// Whether no input of the flow has queued elements.
+    pub fn is_idle(&self) -> bool {
+        self.flow.iter().all(|handle| {
+            let ports = handle.ports();
+            let ports = ports.lock().unwrap();
+            (0..ports.input_len()).all(|index| ports.dyn_input_at(index).is_empty())
+        })
+    }
+
+    /// The updates that failed so far.
+    pub fn errors(&self) -> &[UpdateFailure] {
+        &self.errors
+    }
+
+    pub fn take_errors(&mut self) -> Vec<UpdateFailure> {
+        std::mem::take(&mut self.errors)
+    }
+
+    /// Fails with the first failed update, if any.
+    pub fn check_errors(&self) -> Result<()> {
+        match self.errors.first() {
+            Some(failure) => Err(anyhow!(
+                "Update of node '{}' failed in epoch {}: {:#}",
+                failure.node,
+                failure.epoch,
+                failure.error
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Shuts down all nodes and returns the flow.
+    pub fn shutdown(self) -> Result<Flow> {
+        if self.started {
+            self.flow
+                .shutdown_all()
+                .context("Unable to shutdown all nodes")?;
+        }
+        Ok(self.flow)
+    }
+}
@@ -0,0 +1,181 @@
+//! Property-based tests of single nodes with [`proptest`](::proptest).
+//!
+//! [`check_node`] sends generated sequences of stimuli to a fresh node, updates it after each
+//! element and checks a property of the resulting [`Run`]. Failing sequences are shrunk to a
+//! minimal one. A [`Stimulus`] sends values of one type to any input. Nodes whose inputs have
+//! different types are tested with an enum implementing [`AnyStimulus`].
+
+use ::proptest::{
+    collection::{vec, SizeRange},
+    strategy::Strategy,
+    test_runner::{Config, TestCaseError, TestError, TestRunner},
+};
+use std::{any::Any, fmt, rc::Rc};
+
+use crate::{
+    connection::{connect, Input, Output, RuntimeConnectable},
+    node::{Context, Node, State, UpdateError},
+    testing::Probe,
+};
+
+/// A generated element for the input `port` of the node under test.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stimulus<T> {
+    pub port: usize,
+    pub value: T,
+}
+
+/// An element for one of the inputs of the node under test, e.g. an enum with a variant per
+/// input.
+pub trait AnyStimulus: Clone + fmt::Debug + 'static {
+    /// The index of the input the element is sent to.
+    fn port(&self) -> usize;
+
+    /// Sends the element to `input`, the input at [`AnyStimulus::port`], see [`send_to`].
+    /// Returns whether the input accepts the element.
+    fn send_to(&self, input: Rc<dyn Any>) -> bool;
+}
+
+impl<T> AnyStimulus for Stimulus<T>
+where
+    T: Clone + fmt::Debug + 'static,
+{
+    fn port(&self) -> usize {
+        self.port
+    }
+
+    fn send_to(&self, input: Rc<dyn Any>) -> bool {
+        send_to(input, self.value.clone())
+    }
+}
+
+/// Sends `value` to `input` if it is an `Input<T>`. Returns whether it is.
+pub fn send_to<T: 'static>(input: Rc<dyn Any>, value: T) -> bool {
+    match input.downcast::<Input<T>>() {
+        Ok(input) => {
+            let _ = input.send(value);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Sequences of `len` stimuli for the first `ports` inputs, with values generated by `value`.
+pub fn stimuli<S>(
+    ports: usize,
+    value: S,
+    len: impl Into<SizeRange>,
+) -> impl Strategy<Value = Vec<Stimulus<S::Value>>>
+where
+    S: Strategy,
+    S::Value: Clone,
+{
+    assert!(ports > 0, "A node under test needs at least one input.");
+    vec(
+        (0..ports, value).prop_map(|(port, value)| Stimulus { port, value }),
+        len,
+    )
+}
+
+/// What a node did while receiving a sequence of stimuli.
+pub struct Run<O> {
+    /// The elements sent by each output, by output index. All outputs have to send `O`.
+    pub outputs: Vec<Vec<O>>,
+    /// The failed updates, with the index of the stimulus received before the update.
+    pub errors: Vec<(usize, UpdateError)>,
+}
+
+impl<O> fmt::Debug for Run<O>
+where
+    O: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Run")
+            .field("outputs", &self.outputs)
+            .field("errors", &self.errors)
+            .finish()
+    }
+}
+
+/// Checks `property` for nodes created by `build` against sequences generated by `strategy`.
+///
+/// For each sequence a new node is created, initialized and made ready. Every stimulus is sent
+/// to its input, followed by an update of the node. Returns the minimal failing sequence if the
+/// property does not hold. Fails if an input does not accept its stimulus, or an output does not
+/// send `O`.
+pub fn check_node<N, T, O, S, B, P>(
+    config: Config,
+    strategy: S,
+    build: B,
+    property: P,
+) -> Result<(), TestError<Vec<T>>>
+where
+    N: Node + RuntimeConnectable,
+    T: AnyStimulus,
+    O: 'static,
+    S: Strategy<Value = Vec<T>>,
+    B: Fn(State<Context>) -> N,
+    P: Fn(&[T], &Run<O>) -> Result<(), TestCaseError>,
+{
+    let mut runner = TestRunner::new(config);
+    runner.run(&strategy, |stimuli| {
+        let run = run_node(build(State::new(Context::new())), &stimuli)?;
+        property(&stimuli, &run)
+    })
+}
+
+fn run_node<N, T, O>(node: N, stimuli: &[T]) -> Result<Run<O>, TestCaseError>
+where
+    N: Node + RuntimeConnectable,
+    T: AnyStimulus,
+    O: 'static,
+{
+    let probes = (0..node.output_len())
+        .map(|index| {
+            let output = node.output_at(index).downcast::<Output<O>>().map_err(|_| {
+                TestCaseError::fail(format!(
+                    "Output {} of node '{}' does not send the expected element type.",
+                    index,
+                    node.name()
+                ))
+            })?;
+            let probe = Probe::new();
+            connect(Rc::unwrap_or_clone(output), probe.input());
+            Ok(probe)
+        })
+        .collect::<Result<Vec<Probe<O>>, TestCaseError>>()?;
+
+    node.on_init().map_err(fail)?;
+    node.on_ready().map_err(fail)?;
+    let mut errors = Vec::new();
+    for (index, stimulus) in stimuli.iter().enumerate() {
+        let port = stimulus.port();
+        if port >= node.input_len() {
+            return Err(TestCaseError::fail(format!(
+                "Node '{}' has no input {}.",
+                node.name(),
+                port
+            )));
+        }
+        if !stimulus.send_to(node.input_at(port)) {
+            return Err(TestCaseError::fail(format!(
+                "Input {} of node '{}' does not accept the generated values.",
+                port,
+                node.name()
+            )));
+        }
+        if let Err(err) = node.update() {
+            errors.push((index, err));
+        }
+    }
+    node.on_shutdown().map_err(fail)?;
+
+    Ok(Run {
+        outputs: probes.into_iter().map(|probe| probe.take()).collect(),
+        errors,
+    })
+}
+
+fn fail<E: fmt::Display>(err: E) -> TestCaseError {
+    TestCaseError::fail(err.to_string())
+}
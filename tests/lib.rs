@@ -1,2 +1,5 @@
+#![allow(clippy::module_inception)]
+
 mod nodes;
-mod sched;
\ No newline at end of file
+mod sched;
+mod testing;
@@ -0,0 +1,133 @@
+use std::any::Any;
+use std::rc::Rc;
+
+use flowrs::{
+    connection::{Input, Output, RuntimeConnectable},
+    node::{Context, InitError, Node, ReadyError, ShutdownError, State, UpdateError},
+};
+use flowrs_derive::Connectable;
+
+#[derive(Connectable)]
+pub struct DiagnosticNode {
+    name: String,
+
+    #[input]
+    pub input_1: Input<i32>,
+    #[output]
+    pub output_1: Output<i32>,
+    #[output(optional)]
+    pub diagnostics: Output<String>,
+}
+
+impl DiagnosticNode {
+    pub fn new(name: &str, context: State<Context>) -> Self {
+        Self {
+            name: name.into(),
+            input_1: Input::new(),
+            output_1: Output::new(context.clone()),
+            diagnostics: Output::new(context),
+        }
+    }
+}
+
+impl Node for DiagnosticNode {
+    fn on_init(&self) -> Result<(), InitError> {
+        Ok(())
+    }
+
+    fn on_ready(&self) -> Result<(), ReadyError> {
+        Ok(())
+    }
+
+    fn on_shutdown(&self) -> Result<(), ShutdownError> {
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn update(&self) -> Result<(), UpdateError> {
+        while let Ok(v) = self.input_1.next_elem() {
+            let _ = self.diagnostics.clone().send(format!("received {}", v));
+            let _ = self.output_1.clone().send(v);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod connection {
+    use flowrs::{
+        connection::{connect, ConnectError, Edge, Output, RuntimeConnectable},
+        flow::Flow,
+        node::{Context, Node, State},
+        version::Version,
+    };
+    use std::sync::{Arc, Mutex};
+
+    use super::DiagnosticNode;
+
+    #[test]
+    fn should_fail_on_unconnected_output() {
+        let mut output = Output::<i32>::new(State::new(Context::new()));
+        assert!(matches!(output.send(1), Err(ConnectError::NotConnected)));
+    }
+
+    #[test]
+    fn should_run_without_declared_optional_outputs() {
+        let node = DiagnosticNode::new("diagnostic", State::new(Context::new()));
+        let mock_output = Edge::new();
+        connect(node.output_1.clone(), mock_output.clone());
+
+        node.input_1.send(7).unwrap();
+        node.update().unwrap();
+        assert_eq!(mock_output.next_elem().unwrap(), 7);
+        assert!(matches!(
+            node.diagnostics.clone().send("dropped".into()),
+            Err(ConnectError::NotConnected)
+        ));
+    }
+
+    #[test]
+    fn should_mark_declared_optional_outputs() {
+        let context = State::new(Context::new());
+        let node = DiagnosticNode::new("diagnostic", context.clone());
+        assert!(!node.output_is_optional(0));
+        assert!(node.output_is_optional(1));
+
+        let mock_output = Edge::new();
+        connect(node.output_1.clone(), mock_output.clone());
+        let input = node.input_1.clone();
+
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let id = flow.add_node(node).unwrap();
+        assert!(!flow.validate().has_errors());
+
+        input.send(7).unwrap();
+        flow.node(id).unwrap().lock().unwrap().update().unwrap();
+        assert_eq!(mock_output.next_elem().unwrap(), 7);
+    }
+
+    #[test]
+    fn should_call_tap_after_send_without_locks() {
+        let mut output = Output::<i32>::new(State::new(Context::new()));
+        let edge = Edge::new();
+        connect(output.clone(), edge.clone());
+        let sent = Arc::new(Mutex::new(Vec::new()));
+
+        let (tap_output, tap_edge, tap_sent) = (output.clone(), edge.clone(), sent.clone());
+        edge.set_tap(move |elem: &i32| {
+            // The tap may use the output and its edge, e.g. to remove itself.
+            assert!(tap_output.is_connected());
+            tap_edge.clear_tap();
+            let (elem, edge, sent) = (*elem, tap_edge.clone(), tap_sent.clone());
+            move || sent.lock().unwrap().push((elem, edge.len()))
+        });
+        output.send(1).unwrap();
+        output.send(2).unwrap();
+        // The element was queued when the tap recorded it.
+        assert_eq!(*sent.lock().unwrap(), vec![(1, 1)]);
+        assert_eq!(edge.len(), 2);
+    }
+}
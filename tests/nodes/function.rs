@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod function {
+    use flowrs::{
+        flow::Flow,
+        function::{FnNode, FnNode2, FnSink},
+        node::{Context, State},
+        testing::FlowHarness,
+        version::Version,
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn should_build_nodes_from_closures() {
+        let context = State::new(Context::new());
+        let lengths = Arc::new(Mutex::new(Vec::new()));
+        let sink = lengths.clone();
+
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let parse = flow
+            .add_node(FnNode::new("parse", context.clone(), |s: String| {
+                s.parse::<usize>().unwrap_or_default()
+            }))
+            .unwrap();
+        let repeat = flow
+            .add_node(FnNode2::new(
+                "repeat",
+                context.clone(),
+                |n: usize, s: String| s.repeat(n),
+            ))
+            .unwrap();
+        let len = flow
+            .add_node(FnSink::new("len", move |s: String| {
+                sink.lock().unwrap().push(s.len())
+            }))
+            .unwrap();
+        flow.connect::<usize>(parse, 0, repeat, 0).unwrap();
+        flow.connect::<String>(repeat, 0, len, 0).unwrap();
+
+        let mut harness = FlowHarness::new(flow, context);
+        let counts = harness.feeder::<String>("parse", 0).unwrap();
+        let words = harness.feeder::<String>("repeat", 1).unwrap();
+        counts.send_all(["2".to_string(), "x".to_string(), "3".to_string()]);
+        words.send_all(["ab".to_string(), "c".to_string()]);
+        harness.run_until_idle().unwrap();
+        assert_eq!(*lengths.lock().unwrap(), vec![4, 0]);
+
+        words.send("d".to_string());
+        harness.run_until_idle().unwrap();
+        assert_eq!(*lengths.lock().unwrap(), vec![4, 0, 3]);
+        harness.shutdown().unwrap();
+    }
+}
@@ -0,0 +1,226 @@
+#[cfg(test)]
+mod join {
+    use flowrs::{
+        definition::FlowDefinition,
+        flow::Flow,
+        node::{Clock, Context, State},
+        nodes::std::{KeyedJoin, Unmatched, Zip},
+        registry::NodeRegistry,
+        testing::FlowHarness,
+        version::Version,
+    };
+    use std::time::Duration;
+
+    fn flow() -> Flow {
+        Flow::new("flow_1", Version::new(1, 0, 0))
+    }
+
+    #[test]
+    fn should_evict_oldest_elements_beyond_capacity() {
+        let context = State::new(Context::new());
+        let mut flow = flow();
+        flow.add_node(Zip::<i32, char>::with_capacity("zip", context.clone(), 2))
+            .unwrap();
+
+        let mut harness = FlowHarness::new(flow, context);
+        let lhs = harness.feeder::<i32>("zip", 0).unwrap();
+        let rhs = harness.feeder::<char>("zip", 1).unwrap();
+        let pairs = harness.probe::<(i32, char)>("zip", 0).unwrap();
+        let unmatched = harness.probe::<Unmatched<i32, char>>("zip", 1).unwrap();
+        lhs.send_all([1, 2, 3, 4]);
+        rhs.send('a');
+        harness.run_until_idle().unwrap();
+        assert_eq!(pairs.take(), vec![(1, 'a')]);
+        assert_eq!(unmatched.take(), vec![Unmatched::Input1(2)]);
+
+        rhs.send_all(['b', 'c', 'd']);
+        harness.run_until_idle().unwrap();
+        assert_eq!(pairs.take(), vec![(3, 'b'), (4, 'c')]);
+        assert!(unmatched.is_empty());
+    }
+
+    #[test]
+    fn should_join_elements_by_key_in_any_order() {
+        let context = State::new(Context::new());
+        let mut flow = flow();
+        flow.add_node(KeyedJoin::new(
+            "join",
+            context.clone(),
+            |order: &(u32, &'static str)| order.0,
+            |payment: &(u32, f64)| payment.0,
+        ))
+        .unwrap();
+
+        let mut harness = FlowHarness::new(flow, context);
+        let orders = harness.feeder::<(u32, &'static str)>("join", 0).unwrap();
+        let payments = harness.feeder::<(u32, f64)>("join", 1).unwrap();
+        let joined = harness
+            .probe::<((u32, &'static str), (u32, f64))>("join", 0)
+            .unwrap();
+        payments.send_all([(2, 5.0), (1, 3.5)]);
+        harness.run_until_idle().unwrap();
+        orders.send_all([(1, "tea"), (3, "cake"), (2, "coffee"), (2, "water")]);
+        harness.run_until_idle().unwrap();
+        assert_eq!(
+            joined.take(),
+            vec![((1, "tea"), (1, 3.5)), ((2, "coffee"), (2, 5.0))]
+        );
+
+        payments.send_all([(2, 1.0), (3, 4.0)]);
+        harness.run_until_idle().unwrap();
+        // Elements with equal keys are paired in arrival order.
+        assert_eq!(
+            joined.take(),
+            vec![((2, "water"), (2, 1.0)), ((3, "cake"), (3, 4.0))]
+        );
+    }
+
+    #[test]
+    fn should_send_timed_out_elements_to_unmatched() {
+        let clock = Clock::manual();
+        let mut context = Context::new();
+        context.set_clock(clock.clone());
+        let context = State::new(context);
+        let mut flow = flow();
+        flow.add_node(KeyedJoin::with_timeout(
+            "join",
+            context.clone(),
+            Duration::from_millis(40),
+            |lhs: &i32| *lhs,
+            |rhs: &i32| *rhs,
+        ))
+        .unwrap();
+
+        let mut harness = FlowHarness::new(flow, context);
+        let lhs = harness.feeder::<i32>("join", 0).unwrap();
+        let rhs = harness.feeder::<i32>("join", 1).unwrap();
+        let joined = harness.probe::<(i32, i32)>("join", 0).unwrap();
+        let unmatched = harness.probe::<Unmatched<i32, i32>>("join", 1).unwrap();
+        lhs.send_all([1, 2]);
+        rhs.send(3);
+        harness.run_until_idle().unwrap();
+        assert!(unmatched.is_empty());
+
+        clock.advance(Duration::from_millis(50));
+        rhs.send(2);
+        harness.run_until_idle().unwrap();
+        assert_eq!(joined.take(), vec![(2, 2)]);
+        assert_eq!(
+            unmatched.take(),
+            vec![Unmatched::Input1(1), Unmatched::Input2(3)]
+        );
+
+        lhs.send(4);
+        harness.run_until_idle().unwrap();
+        clock.advance(Duration::from_millis(50));
+        harness.run_epoch().unwrap();
+        assert_eq!(unmatched.take(), vec![Unmatched::Input1(4)]);
+        assert!(joined.is_empty());
+    }
+
+    #[test]
+    fn should_evict_oldest_unmatched_keys_beyond_capacity() {
+        let context = State::new(Context::new());
+        let mut flow = flow();
+        flow.add_node(KeyedJoin::with_capacity(
+            "join",
+            context.clone(),
+            2,
+            |lhs: &i32| *lhs,
+            |rhs: &i32| *rhs,
+        ))
+        .unwrap();
+
+        let mut harness = FlowHarness::new(flow, context);
+        let lhs = harness.feeder::<i32>("join", 0).unwrap();
+        let rhs = harness.feeder::<i32>("join", 1).unwrap();
+        let joined = harness.probe::<(i32, i32)>("join", 0).unwrap();
+        let unmatched = harness.probe::<Unmatched<i32, i32>>("join", 1).unwrap();
+        lhs.send_all([1, 2, 1, 3]);
+        rhs.send(4);
+        harness.run_until_idle().unwrap();
+        assert_eq!(
+            unmatched.take(),
+            vec![Unmatched::Input1(1), Unmatched::Input1(2)]
+        );
+
+        rhs.send_all([1, 3]);
+        harness.run_until_idle().unwrap();
+        assert_eq!(joined.take(), vec![(1, 1), (3, 3)]);
+        assert!(unmatched.is_empty());
+    }
+
+    #[test]
+    fn should_bound_joins_by_timeout_and_capacity() {
+        let clock = Clock::manual();
+        let mut context = Context::new();
+        context.set_clock(clock.clone());
+        let context = State::new(context);
+        let mut flow = flow();
+        flow.add_node(KeyedJoin::with_bounds(
+            "join",
+            context.clone(),
+            Some(Duration::from_millis(40)),
+            Some(2),
+            |lhs: &i32| *lhs,
+            |rhs: &i32| *rhs,
+        ))
+        .unwrap();
+
+        let mut harness = FlowHarness::new(flow, context);
+        let lhs = harness.feeder::<i32>("join", 0).unwrap();
+        let _rhs = harness.feeder::<i32>("join", 1).unwrap();
+        let joined = harness.probe::<(i32, i32)>("join", 0).unwrap();
+        let unmatched = harness.probe::<Unmatched<i32, i32>>("join", 1).unwrap();
+        lhs.send_all([1, 2, 3]);
+        harness.run_until_idle().unwrap();
+        assert_eq!(unmatched.take(), vec![Unmatched::Input1(1)]);
+
+        clock.advance(Duration::from_millis(50));
+        harness.run_epoch().unwrap();
+        assert_eq!(
+            unmatched.take(),
+            vec![Unmatched::Input1(2), Unmatched::Input1(3)]
+        );
+        assert!(joined.is_empty());
+    }
+
+    #[test]
+    fn should_construct_zip_from_registry() {
+        let mut registry = NodeRegistry::new();
+        registry.register_typed_node::<Zip<i32, i32>>("Zip");
+        let definition = FlowDefinition::from_json(
+            r#"{
+                "name": "zip",
+                "version": "1.0.0",
+                "nodes": [
+                    { "name": "bounded", "kind": "Zip", "props": { "capacity": 1 } },
+                    { "name": "unbounded", "kind": "Zip", "props": {} }
+                ],
+                "edges": []
+            }"#,
+        )
+        .unwrap();
+        assert!(registry
+            .validate_props("zip", "Zip", &serde_json::json!({ "capacity": -1 }))
+            .is_err());
+
+        let context = State::new(Context::new());
+        let flow = definition.build(&registry, context.clone()).unwrap();
+        let mut harness = FlowHarness::new(flow, context);
+        let bounded = harness.feeder::<i32>("bounded", 0).unwrap();
+        let pairs = harness.probe::<(i32, i32)>("bounded", 0).unwrap();
+        let evicted = harness.probe::<Unmatched<i32, i32>>("bounded", 1).unwrap();
+        let unbounded = harness.feeder::<i32>("unbounded", 0).unwrap();
+        harness.probe::<(i32, i32)>("unbounded", 0).unwrap();
+        let kept = harness
+            .probe::<Unmatched<i32, i32>>("unbounded", 1)
+            .unwrap();
+        bounded.send_all([1, 2]);
+        unbounded.send_all([1, 2]);
+        harness.run_until_idle().unwrap();
+        assert!(pairs.is_empty());
+        assert_eq!(evicted.values(), vec![Unmatched::Input1(1)]);
+        assert!(kept.is_empty());
+    }
+}
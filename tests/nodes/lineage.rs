@@ -0,0 +1,121 @@
+#[cfg(test)]
+mod lineage {
+    use flowrs::{
+        connection::{connect, Edge, Output},
+        flow::Flow,
+        lineage::LineageRecorder,
+        node::{Context, State},
+        version::Version,
+    };
+    use serde_json::Value;
+
+    use crate::{nodes::node::AddNode, sched::flow::CounterNode};
+
+    #[test]
+    fn should_reconstruct_message_path() {
+        let recorder = LineageRecorder::new();
+        let mut context = Context::new();
+        context.set_lineage(recorder.clone());
+        let context = State::new(context);
+
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let id1 = flow
+            .add_node(CounterNode::new("counter_1", context.clone()))
+            .unwrap();
+        let counter_2 = CounterNode::new("counter_2", context.clone());
+        let mock_output = Edge::new();
+        connect(counter_2.output_1.clone(), mock_output.clone());
+        let mut external = Output::new(context);
+        connect(external.clone(), counter_2.input_1.clone());
+        let id2 = flow.add_node(counter_2).unwrap();
+        flow.connect_serializable::<i32>(id1, 0, id2, 0).unwrap();
+
+        let counter_1_input = flow.handle(id1).unwrap().ports();
+        let counter_1_input = counter_1_input.lock().unwrap().input_at(0);
+        let counter_1_input = counter_1_input.downcast::<Edge<i32>>().unwrap();
+        external.send(0).unwrap();
+        let mut source = Output::new(State::new(Context::new()));
+        connect(source.clone(), counter_1_input.as_ref().clone());
+        source.send(1).unwrap();
+
+        flow.init_all().unwrap();
+        flow.ready_all().unwrap();
+        for handle in flow.iter() {
+            handle.update().unwrap();
+        }
+
+        let (_, first) = mock_output.next_with_envelope().unwrap();
+        let (value, second) = mock_output.next_with_envelope().unwrap();
+        assert_eq!(value, 1);
+        let (first, second) = (first.unwrap(), second.unwrap());
+        assert_eq!(first.origin.as_deref(), Some("counter_2"));
+
+        let path = recorder.path(first.id);
+        assert_eq!(path.len(), 2);
+        assert_eq!(path[0].origin, None);
+
+        // The element sent without lineage starts its path at counter_1.
+        let path: Vec<_> = recorder
+            .path(second.id)
+            .into_iter()
+            .map(|envelope| envelope.origin.unwrap())
+            .collect();
+        assert_eq!(path, vec!["counter_1", "counter_2"]);
+        assert_eq!(recorder.messages().len(), 4);
+    }
+
+    #[test]
+    fn should_derive_from_all_received_messages() {
+        let recorder = LineageRecorder::new();
+        let mut context = Context::new();
+        context.set_lineage(recorder.clone());
+        let context = State::new(context);
+
+        let add = AddNode::<i32, i32, i32>::new("add", context.clone(), Value::Null);
+        let mock_output = Edge::new();
+        connect(add.output_1.clone(), mock_output.clone());
+        let mut lhs = Output::new(context.clone());
+        let mut rhs = Output::new(context);
+        connect(lhs.clone(), add.input_1.clone());
+        connect(rhs.clone(), add.input_2.clone());
+        lhs.send(1).unwrap();
+        rhs.send(2).unwrap();
+
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let id = flow.add_node(add).unwrap();
+        flow.init_all().unwrap();
+        flow.ready_all().unwrap();
+        flow.handle(id).unwrap().update().unwrap();
+
+        let (sum, envelope) = mock_output.next_with_envelope().unwrap();
+        assert_eq!(sum, 3);
+        let envelope = envelope.unwrap();
+        assert_eq!(envelope.origin.as_deref(), Some("add"));
+        assert_eq!(envelope.parents.len(), 2);
+        assert_eq!(recorder.path(envelope.id).len(), 3);
+    }
+
+    #[test]
+    fn should_evict_oldest_messages_beyond_capacity() {
+        let recorder = LineageRecorder::with_capacity(2);
+        let mut context = Context::new();
+        context.set_lineage(recorder.clone());
+        let mut output = Output::new(State::new(context));
+        let edge = Edge::new();
+        connect(output.clone(), edge.clone());
+        for i in 0..3 {
+            output.send(i).unwrap();
+        }
+
+        let (_, first) = edge.next_with_envelope().unwrap();
+        assert!(recorder.path(first.unwrap().id).is_empty());
+        let ids: Vec<_> = std::iter::from_fn(|| edge.next_with_envelope().ok())
+            .map(|(_, envelope)| envelope.unwrap().id)
+            .collect();
+        let recorded: Vec<_> = recorder.messages().iter().map(|m| m.id).collect();
+        assert_eq!(recorded, ids);
+
+        recorder.clear();
+        assert!(recorder.messages().is_empty());
+    }
+}
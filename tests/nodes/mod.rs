@@ -1 +1,9 @@
-pub mod node;
\ No newline at end of file
+pub mod connection;
+pub mod function;
+pub mod join;
+pub mod lineage;
+pub mod node;
+pub mod node_macro;
+pub mod std;
+pub mod subflow;
+pub mod window;
@@ -155,7 +155,8 @@ mod nodes {
 
         let expected = 3;
         let actual = mock_output.next_elem()?;
-        Ok(assert!(expected == actual))
+        assert!(expected == actual);
+        Ok(())
     }
 
     /// Scenario:
@@ -186,12 +187,13 @@ mod nodes {
             actual.push(curr)
         }
         let exected = vec![100; 100];
-        Ok(assert!(
+        assert!(
             exected == actual,
             "expected was: {:?} while actual was {:?}",
             exected,
             actual
-        ))
+        );
+        Ok(())
     }
 
     #[test]
@@ -237,7 +239,8 @@ mod nodes {
             let curr = mock_output.next_elem();
             actual.push(curr)
         }
-        Ok(assert!(!actual.is_empty()))
+        assert!(!actual.is_empty());
+        Ok(())
     }
 
     #[test]
@@ -0,0 +1,106 @@
+use flowrs::connection::{Input, Output, RuntimeConnectable};
+use flowrs::node::{Context, Node, State, UpdateError};
+use flowrs_derive::Connectable;
+
+use std::{any::Any, rc::Rc};
+
+/// Sums numbers and counts words, rejecting negative numbers.
+#[derive(Connectable)]
+pub struct TallyNode {
+    name: String,
+    words: State<usize>,
+
+    #[input]
+    pub numbers: Input<i32>,
+    #[input]
+    pub words_in: Input<String>,
+    #[output]
+    pub sums: Output<i32>,
+}
+
+impl TallyNode {
+    pub fn new(name: &str, context: State<Context>) -> Self {
+        Self {
+            name: name.into(),
+            words: State::new(0),
+            numbers: Input::new(),
+            words_in: Input::new(),
+            sums: Output::new(context),
+        }
+    }
+}
+
+#[flowrs::node]
+impl Node for TallyNode {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[on_input(numbers)]
+    fn handle_number(&self, v: i32) -> Result<(), UpdateError> {
+        if v < 0 {
+            return Err(UpdateError::SequenceError {
+                node: self.name.clone(),
+                message: "Negative number.".into(),
+            });
+        }
+        let _ = self.sums.clone().send(v * 2);
+        Ok(())
+    }
+
+    #[on_input(words_in)]
+    fn handle_word(&self, _v: String) {
+        *self.words.0.lock().unwrap() += 1;
+    }
+}
+
+#[cfg(test)]
+mod node_macro {
+    use flowrs::{
+        flow::Flow,
+        node::{Context, Node, State},
+        testing::FlowHarness,
+        version::Version,
+    };
+
+    use crate::nodes::node_macro::TallyNode;
+
+    #[test]
+    fn should_dispatch_inputs_to_handlers() {
+        let context = State::new(Context::new());
+        let node = TallyNode::new("tally", context.clone());
+        let words = node.words.clone();
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        flow.add_node(node).unwrap();
+
+        let mut harness = FlowHarness::new(flow, context);
+        let numbers = harness.feeder::<i32>("tally", 0).unwrap();
+        let words_in = harness.feeder::<String>("tally", 1).unwrap();
+        let sums = harness.probe::<i32>("tally", 0).unwrap();
+        numbers.send_all([1, 2]);
+        words_in.send_all(["a".to_string(), "b".to_string()]);
+        assert_eq!(harness.run_until_idle().unwrap(), 1);
+        assert_eq!(sums.take(), vec![2, 4]);
+        assert_eq!(*words.0.lock().unwrap(), 2);
+
+        // A failing handler stops the update, the remaining elements stay queued.
+        numbers.send_all([-1, 3]);
+        words_in.send("c".to_string());
+        harness.run_epoch().unwrap();
+        assert_eq!(harness.take_errors().len(), 1);
+        assert!(sums.is_empty());
+        assert_eq!(*words.0.lock().unwrap(), 2);
+        harness.run_until_idle().unwrap();
+        assert_eq!(sums.take(), vec![6]);
+        assert_eq!(*words.0.lock().unwrap(), 3);
+    }
+
+    #[test]
+    fn should_keep_handlers_callable() {
+        let node = TallyNode::new("tally", State::new(Context::new()));
+        node.handle_word("a".into());
+        assert!(node.handle_number(-1).is_err());
+        node.update().unwrap();
+        assert_eq!(*node.words.0.lock().unwrap(), 1);
+    }
+}
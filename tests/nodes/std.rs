@@ -0,0 +1,313 @@
+#[cfg(test)]
+mod std {
+    use flowrs::{
+        definition::FlowDefinition,
+        executor::{Executor, MultiThreadedExecutor},
+        flow::Flow,
+        node::{Clock, Context, State},
+        nodes::std::{
+            Arithmetic, Broadcast, Buffer, Collect, Constant, Counter, Debounce, Filter, Log, Map,
+            Merge, Operator, Split, Zip,
+        },
+        registry::NodeRegistry,
+        scheduler::RoundRobinScheduler,
+        testing::FlowHarness,
+        version::Version,
+    };
+    use std::{
+        io::{self, Write},
+        sync::{Arc, Mutex},
+        thread,
+        time::Duration,
+    };
+
+    fn flow() -> Flow {
+        Flow::new("flow_1", Version::new(1, 0, 0))
+    }
+
+    #[test]
+    fn should_transform_and_route_elements() {
+        let context = State::new(Context::new());
+        let mut flow = flow();
+        let broadcast = flow
+            .add_node(Broadcast::<i32>::new("broadcast", context.clone()))
+            .unwrap();
+        let map = flow
+            .add_node(Map::new("map", context.clone(), |x: i32| x * 10))
+            .unwrap();
+        let filter = flow
+            .add_node(Filter::new("filter", context.clone(), |x: &i32| x % 2 == 1))
+            .unwrap();
+        let merge = flow
+            .add_node(Merge::<i32>::new("merge", context.clone()))
+            .unwrap();
+        let split = flow
+            .add_node(Split::new("split", context.clone(), |x: &i32| *x > 5))
+            .unwrap();
+        flow.connect::<i32>(broadcast, 0, map, 0).unwrap();
+        flow.connect::<i32>(broadcast, 1, filter, 0).unwrap();
+        flow.connect::<i32>(map, 0, merge, 0).unwrap();
+        flow.connect::<i32>(filter, 0, merge, 1).unwrap();
+        flow.connect::<i32>(merge, 0, split, 0).unwrap();
+
+        let mut harness = FlowHarness::new(flow, context);
+        let input = harness.feeder::<i32>("broadcast", 0).unwrap();
+        let large = harness.probe::<i32>("split", 0).unwrap();
+        input.send_all([1, 2, 3]);
+        harness.run_until_idle().unwrap();
+        // Elements of the unconnected optional output are dropped.
+        assert_eq!(large.values(), vec![10, 20, 30]);
+    }
+
+    #[test]
+    fn should_pair_elements_in_arrival_order() {
+        let context = State::new(Context::new());
+        let mut flow = flow();
+        flow.add_node(Zip::<i32, String>::new("zip", context.clone()))
+            .unwrap();
+        flow.add_node(Arithmetic::<i32>::new(
+            "div",
+            context.clone(),
+            Operator::Div,
+        ))
+        .unwrap();
+
+        let mut harness = FlowHarness::new(flow, context);
+        let (numbers, names) = (
+            harness.feeder("zip", 0).unwrap(),
+            harness.feeder("zip", 1).unwrap(),
+        );
+        let (lhs, rhs) = (
+            harness.feeder("div", 0).unwrap(),
+            harness.feeder("div", 1).unwrap(),
+        );
+        let pairs = harness.probe::<(i32, String)>("zip", 0).unwrap();
+        let quotients = harness.probe::<i32>("div", 0).unwrap();
+
+        numbers.send_all([1, 2, 3]);
+        names.send("one".to_string());
+        lhs.send_all([10, 20, 30]);
+        rhs.send_all([2, 0]);
+        harness.run_until_idle().unwrap();
+        assert_eq!(pairs.take(), vec![(1, "one".to_string())]);
+        assert_eq!(quotients.take(), vec![5]);
+        assert_eq!(harness.take_errors().len(), 1);
+
+        names.send_all(["two".to_string(), "three".to_string()]);
+        rhs.send(3);
+        harness.run_until_idle().unwrap();
+        assert_eq!(
+            pairs.take(),
+            vec![(2, "two".to_string()), (3, "three".to_string())]
+        );
+        assert_eq!(quotients.take(), vec![10]);
+    }
+
+    #[test]
+    fn should_fail_on_arithmetic_overflow() {
+        let context = State::new(Context::new());
+        let mut flow = flow();
+        flow.add_node(Arithmetic::<u8>::new("mul", context.clone(), Operator::Mul))
+            .unwrap();
+
+        let mut harness = FlowHarness::new(flow, context);
+        let lhs = harness.feeder::<u8>("mul", 0).unwrap();
+        let rhs = harness.feeder::<u8>("mul", 1).unwrap();
+        let products = harness.probe::<u8>("mul", 0).unwrap();
+        lhs.send_all([16, 15]);
+        rhs.send_all([16, 17]);
+        harness.run_until_idle().unwrap();
+        assert_eq!(products.take(), vec![255]);
+        assert_eq!(harness.take_errors().len(), 1);
+    }
+
+    #[test]
+    fn should_buffer_and_count_elements() {
+        let context = State::new(Context::new());
+        let mut flow = flow();
+        let buffer = flow
+            .add_node(Buffer::<i32>::new("buffer", context.clone(), 2))
+            .unwrap();
+        let counter = flow
+            .add_node(Counter::<Vec<i32>>::new("counter", context.clone()))
+            .unwrap();
+        let collect = Collect::<u64>::new("collect");
+        let counts = collect.values();
+        let sink = flow.add_node(collect).unwrap();
+        flow.connect::<Vec<i32>>(buffer, 0, counter, 0).unwrap();
+        flow.connect::<u64>(counter, 0, sink, 0).unwrap();
+
+        let mut harness = FlowHarness::new(flow, context);
+        let input = harness.feeder::<i32>("buffer", 0).unwrap();
+        input.send_all(1..=5);
+        harness.run_until_idle().unwrap();
+        assert_eq!(*counts.lock().unwrap(), vec![1, 2]);
+        let checkpoint = harness.flow().checkpoint().unwrap();
+        assert_eq!(checkpoint.nodes["counter"]["count"], 2);
+    }
+
+    #[test]
+    fn should_not_preallocate_huge_buffers() {
+        let mut registry = NodeRegistry::new();
+        registry.register_typed_node::<Buffer<i32>>("Buffer");
+        let definition = FlowDefinition::from_json(&format!(
+            r#"{{
+                "name": "buffer",
+                "version": "1.0.0",
+                "nodes": [{{ "name": "buffer", "kind": "Buffer", "props": {{ "capacity": {} }} }}],
+                "edges": []
+            }}"#,
+            usize::MAX
+        ))
+        .unwrap();
+
+        let context = State::new(Context::new());
+        let flow = definition.build(&registry, context.clone()).unwrap();
+        let mut harness = FlowHarness::new(flow, context);
+        let input = harness.feeder::<i32>("buffer", 0).unwrap();
+        let batches = harness.probe::<Vec<i32>>("buffer", 0).unwrap();
+        input.send_all([1, 2]);
+        harness.run_until_idle().unwrap();
+        assert!(batches.is_empty());
+    }
+
+    /// A writer whose output can be inspected after handing it to a node.
+    #[derive(Clone, Default)]
+    struct SharedWriter(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn should_write_log_lines() {
+        let context = State::new(Context::new());
+        let writer = SharedWriter::default();
+        let mut flow = flow();
+        flow.add_node(Log::<i32>::with_writer("log", writer.clone()))
+            .unwrap();
+
+        let mut harness = FlowHarness::new(flow, context);
+        let input = harness.feeder::<i32>("log", 0).unwrap();
+        input.send_all([1, 2]);
+        harness.run_until_idle().unwrap();
+        let lines = String::from_utf8(writer.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(lines, "log: 1\nlog: 2\n");
+    }
+
+    #[test]
+    fn should_debounce_elements() {
+        let clock = Clock::manual();
+        let mut context = Context::new();
+        context.set_clock(clock.clone());
+        let context = State::new(context);
+        let mut flow = flow();
+        flow.add_node(Debounce::<i32>::new(
+            "debounce",
+            context.clone(),
+            Duration::from_millis(50),
+        ))
+        .unwrap();
+
+        let mut harness = FlowHarness::new(flow, context);
+        let input = harness.feeder::<i32>("debounce", 0).unwrap();
+        let output = harness.probe::<i32>("debounce", 0).unwrap();
+        input.send_all([1, 2, 3]);
+        harness.run_until_idle().unwrap();
+        assert!(output.is_empty());
+
+        clock.advance(Duration::from_millis(40));
+        harness.run_epoch().unwrap();
+        assert!(output.is_empty());
+        clock.advance(Duration::from_millis(10));
+        harness.run_epoch().unwrap();
+        assert_eq!(output.take(), vec![3]);
+    }
+
+    #[test]
+    fn should_update_debounce_when_delay_is_over() {
+        let context = State::new(Context::new());
+        let mut flow = flow();
+        let constant = flow
+            .add_node(Constant::new("constant", context.clone(), 7))
+            .unwrap();
+        let debounce = flow
+            .add_node(Debounce::<i32>::new(
+                "debounce",
+                context.clone(),
+                Duration::from_millis(50),
+            ))
+            .unwrap();
+        let collect = Collect::<i32>::new("collect");
+        let values = collect.values();
+        let sink = flow.add_node(collect).unwrap();
+        flow.connect::<i32>(constant, 0, debounce, 0).unwrap();
+        flow.connect::<i32>(debounce, 0, sink, 0).unwrap();
+
+        let mut executor = MultiThreadedExecutor::new(1, context);
+        let controller = executor.controller();
+        let handle = thread::spawn(move || executor.run(flow, RoundRobinScheduler::new()));
+        thread::sleep(Duration::from_millis(500));
+        controller.lock().unwrap().cancel();
+        handle.join().unwrap().unwrap();
+        assert_eq!(*values.lock().unwrap(), vec![7]);
+    }
+
+    #[test]
+    fn should_construct_std_nodes_from_registry() {
+        let mut registry = NodeRegistry::new();
+        registry.register_typed_node::<Constant<i32>>("Constant");
+        registry.register_typed_node::<Arithmetic<i32>>("Arithmetic");
+        registry.register_typed_node::<Counter<i32>>("Counter");
+        let definition = FlowDefinition::from_json(
+            r#"{
+                "name": "std",
+                "version": "1.0.0",
+                "nodes": [
+                    { "name": "lhs", "kind": "Constant", "props": { "value": 6 } },
+                    { "name": "rhs", "kind": "Constant", "props": { "value": 4 } },
+                    { "name": "sub", "kind": "Arithmetic", "props": { "operator": "sub" } },
+                    { "name": "counter", "kind": "Counter", "props": { "start": 10 } }
+                ],
+                "edges": [
+                    {
+                        "from": { "node": "lhs", "port": 0 },
+                        "to": { "node": "sub", "port": 0 },
+                        "type": "i32"
+                    },
+                    {
+                        "from": { "node": "rhs", "port": 0 },
+                        "to": { "node": "sub", "port": 1 },
+                        "type": "i32"
+                    },
+                    {
+                        "from": { "node": "sub", "port": 0 },
+                        "to": { "node": "counter", "port": 0 },
+                        "type": "i32"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert!(registry
+            .validate_props(
+                "sub",
+                "Arithmetic",
+                &serde_json::json!({ "operator": "pow" })
+            )
+            .is_err());
+
+        let context = State::new(Context::new());
+        let flow = definition.build(&registry, context.clone()).unwrap();
+        let mut harness = FlowHarness::new(flow, context);
+        let counts = harness.probe::<u64>("counter", 0).unwrap();
+        harness.run_until_idle().unwrap();
+        assert_eq!(counts.values(), vec![11]);
+    }
+}
@@ -0,0 +1,189 @@
+#[cfg(test)]
+mod subflow {
+    use flowrs::{
+        connection::{connect, Edge, Input, Output, RuntimeConnectable},
+        definition::{DefinitionError, FlowDefinition},
+        flow::Flow,
+        node::{Context, State},
+        registry::NodeRegistry,
+        subflow::{ExposedPort, SubflowNode},
+        version::Version,
+    };
+    use serde_json::Value;
+
+    use crate::nodes::node::AddNode;
+
+    const ADDER: &str = r#"{
+        "name": "adder",
+        "version": "1.0.0",
+        "nodes": [{ "name": "add", "kind": "AddNodeI32" }],
+        "inputs": [
+            { "name": "lhs", "node": "add", "port": 0 },
+            { "name": "rhs", "node": "add", "port": 1 }
+        ],
+        "outputs": [{ "name": "sum", "node": "add", "port": 0 }]
+    }"#;
+
+    const PIPELINE: &str = r#"{
+        "name": "pipeline",
+        "version": "1.0.0",
+        "nodes": [
+            { "name": "adder_1", "kind": "Adder" },
+            { "name": "adder_2", "kind": "Adder" }
+        ],
+        "edges": [{
+            "from": { "node": "adder_1", "port": 0 },
+            "to": { "node": "adder_2", "port": 0 },
+            "type": "i32"
+        }]
+    }"#;
+
+    fn registry() -> NodeRegistry {
+        let mut registry = NodeRegistry::new();
+        registry.register_node("AddNodeI32", |name, context, props| {
+            Ok(AddNode::<i32, i32, i32>::new(name, context, props))
+        });
+        registry
+            .register_subflow("Adder", FlowDefinition::from_json(ADDER).unwrap())
+            .unwrap();
+        registry
+    }
+
+    fn input(flow: &Flow, node: &str, index: usize) -> Input<i32> {
+        let ports = flow
+            .handle(flow.node_by_name(node).unwrap())
+            .unwrap()
+            .ports();
+        let input = ports.lock().unwrap().input_at(index);
+        input.downcast::<Input<i32>>().unwrap().as_ref().clone()
+    }
+
+    fn output(flow: &Flow, node: &str, index: usize) -> Output<i32> {
+        let ports = flow
+            .handle(flow.node_by_name(node).unwrap())
+            .unwrap()
+            .ports();
+        let output = ports.lock().unwrap().output_at(index);
+        output.downcast::<Output<i32>>().unwrap().as_ref().clone()
+    }
+
+    fn run_once(flow: &Flow) {
+        flow.init_all().unwrap();
+        flow.ready_all().unwrap();
+        for handle in flow.iter() {
+            handle.update().unwrap();
+        }
+    }
+
+    #[test]
+    fn should_expose_inner_ports() {
+        let context = State::new(Context::new());
+        let mut inner = Flow::new("adder", Version::new(1, 0, 0));
+        let add = inner
+            .add_node(AddNode::<i32, i32, i32>::new(
+                "add",
+                context.clone(),
+                Value::Null,
+            ))
+            .unwrap();
+        let port = |name: &str, index| ExposedPort {
+            name: name.into(),
+            node: add,
+            index,
+        };
+        let subflow = SubflowNode::new(
+            "adder",
+            inner,
+            vec![port("lhs", 0), port("rhs", 1)],
+            vec![port("sum", 0)],
+        )
+        .unwrap();
+        assert_eq!(subflow.input_len(), 2);
+        assert_eq!(subflow.output_name_at(0), "sum");
+
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        flow.add_node(subflow).unwrap();
+        let mock_output = Edge::new();
+        connect(output(&flow, "adder", 0), mock_output.clone());
+        input(&flow, "adder", 0).send(1).unwrap();
+        input(&flow, "adder", 1).send(2).unwrap();
+
+        run_once(&flow);
+        assert_eq!(mock_output.next_elem().unwrap(), 3);
+    }
+
+    #[test]
+    fn should_reject_unknown_exposed_port() {
+        let context = State::new(Context::new());
+        let mut inner = Flow::new("adder", Version::new(1, 0, 0));
+        let add = inner
+            .add_node(AddNode::<i32, i32, i32>::new("add", context, Value::Null))
+            .unwrap();
+        let port = ExposedPort {
+            name: "lhs".into(),
+            node: add,
+            index: 2,
+        };
+        assert!(SubflowNode::new("adder", inner, vec![port], vec![]).is_err());
+    }
+
+    #[test]
+    fn should_nest_subflows_from_json() {
+        let context = State::new(Context::new());
+        let flow = FlowDefinition::from_json(PIPELINE)
+            .unwrap()
+            .build(&registry(), context)
+            .unwrap();
+        assert_eq!(flow.num_nodes(), 2);
+        assert_eq!(flow.edges().len(), 1);
+
+        let mock_output = Edge::new();
+        connect(output(&flow, "adder_2", 0), mock_output.clone());
+        input(&flow, "adder_1", 0).send(1).unwrap();
+        input(&flow, "adder_1", 1).send(2).unwrap();
+        input(&flow, "adder_2", 1).send(3).unwrap();
+
+        run_once(&flow);
+        assert_eq!(mock_output.next_elem().unwrap(), 6);
+    }
+
+    #[test]
+    fn should_reject_recursive_subflows() {
+        let subflow = |name: &str, kind: &str| {
+            FlowDefinition::from_json(&format!(
+                r#"{{
+                    "name": "{name}",
+                    "version": "1.0.0",
+                    "nodes": [{{ "name": "inner", "kind": "{kind}" }}]
+                }}"#
+            ))
+            .unwrap()
+        };
+        let mut registry = registry();
+        for (kind, definition) in [
+            ("Loop", subflow("loop", "Loop")),
+            ("Ping", subflow("ping", "Pong")),
+            ("Pong", subflow("pong", "Ping")),
+            ("Outer", subflow("outer", "Ping")),
+        ] {
+            registry.register_subflow(kind, definition).unwrap();
+        }
+
+        let context = State::new(Context::new());
+        for kind in ["Loop", "Ping", "Outer"] {
+            let err = subflow("main", kind)
+                .build(&registry, context.clone())
+                .err()
+                .unwrap();
+            assert!(
+                matches!(
+                    err.downcast_ref::<DefinitionError>(),
+                    Some(DefinitionError::RecursiveSubflow(_))
+                ),
+                "{:?}",
+                err
+            );
+        }
+        assert!(subflow("main", "Adder").build(&registry, context).is_ok());
+    }
+}
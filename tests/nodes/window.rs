@@ -0,0 +1,226 @@
+#[cfg(test)]
+mod window {
+    use flowrs::{
+        definition::FlowDefinition,
+        flow::Flow,
+        node::{Clock, Context, State},
+        nodes::std::{Aggregator, Fold, Max, Mean, Min, Sum, Window, WindowKind},
+        registry::NodeRegistry,
+        testing::{Feeder, FlowHarness, Probe},
+        version::Version,
+    };
+    use std::time::Duration;
+
+    fn harness<O: Clone + Send + 'static>(
+        window: impl FnOnce(State<Context>) -> Window<i32, O>,
+    ) -> (FlowHarness, Feeder<i32>, Probe<O>) {
+        let context = State::new(Context::new());
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        flow.add_node(window(context.clone())).unwrap();
+        let harness = FlowHarness::new(flow, context);
+        let input = harness.feeder::<i32>("window", 0).unwrap();
+        let output = harness.probe::<O>("window", 0).unwrap();
+        (harness, input, output)
+    }
+
+    #[test]
+    fn should_aggregate_tumbling_count_windows() {
+        let (mut harness, input, output) = harness(|context| {
+            Window::new(
+                "window",
+                context,
+                WindowKind::TumblingCount { size: 2 },
+                Sum,
+            )
+        });
+        input.send_all([1, 2, 3, 4, 5]);
+        harness.run_until_idle().unwrap();
+        // The last window is not full yet.
+        assert_eq!(output.take(), vec![3, 7]);
+    }
+
+    #[test]
+    fn should_aggregate_sliding_count_windows() {
+        let (mut harness, input, output) = harness(|context| {
+            let kind = WindowKind::SlidingCount { size: 3, slide: 2 };
+            Window::new("window", context, kind, Max)
+        });
+        input.send_all([5, 1, 2, 7, 3, 0, 4]);
+        harness.run_until_idle().unwrap();
+        assert_eq!(output.take(), vec![5, 7, 4]);
+    }
+
+    #[test]
+    fn should_aggregate_with_custom_folds() {
+        let (mut harness, input, output) = harness(|context| {
+            let concat = Fold::new(String::new(), |acc: String, x: &i32| acc + &x.to_string());
+            Window::new(
+                "window",
+                context,
+                WindowKind::TumblingCount { size: 3 },
+                concat,
+            )
+        });
+        input.send_all([1, 2, 3, 4, 5, 6]);
+        harness.run_until_idle().unwrap();
+        assert_eq!(output.take(), vec!["123".to_string(), "456".to_string()]);
+    }
+
+    #[test]
+    fn should_average_any_primitive_number() {
+        assert_eq!(Mean.aggregate(&[1u64, 2, 6]), 3.0);
+        assert_eq!(Mean.aggregate(&[-4i64, 1]), -1.5);
+        assert_eq!(Mean.aggregate(&[usize::MAX, usize::MAX]), usize::MAX as f64);
+        assert_eq!(Mean.aggregate(&[0.5f32, 1.0]), 0.75);
+    }
+
+    /// Lets the nodes of `harness` act on a clock that only moves when it is advanced.
+    fn manual_clock(harness: &FlowHarness) -> Clock {
+        let clock = Clock::manual();
+        harness.context().0.lock().unwrap().set_clock(clock.clone());
+        clock
+    }
+
+    #[test]
+    fn should_close_tumbling_time_windows() {
+        let (mut harness, input, output) = harness(|context| {
+            let kind = WindowKind::TumblingTime {
+                size: Duration::from_millis(40),
+            };
+            Window::new("window", context, kind, Mean)
+        });
+        let clock = manual_clock(&harness);
+        input.send_all([1, 2, 6]);
+        harness.run_until_idle().unwrap();
+        assert!(output.is_empty());
+
+        clock.advance(Duration::from_millis(50));
+        input.send(10);
+        harness.run_until_idle().unwrap();
+        assert_eq!(output.take(), vec![3.0]);
+
+        // The second window started when the first one ended, not with its first element.
+        clock.advance(Duration::from_millis(30));
+        harness.run_epoch().unwrap();
+        assert_eq!(output.take(), vec![10.0]);
+
+        // Empty windows are skipped, the boundaries stay in place.
+        clock.advance(Duration::from_millis(100));
+        input.send(5);
+        harness.run_until_idle().unwrap();
+        clock.advance(Duration::from_millis(15));
+        harness.run_epoch().unwrap();
+        assert!(output.is_empty());
+        clock.advance(Duration::from_millis(5));
+        harness.run_epoch().unwrap();
+        assert_eq!(output.take(), vec![5.0]);
+    }
+
+    #[test]
+    fn should_close_sliding_time_windows() {
+        let (mut harness, input, output) = harness(|context| {
+            let kind = WindowKind::SlidingTime {
+                size: Duration::from_millis(60),
+                slide: Duration::from_millis(30),
+            };
+            Window::new("window", context, kind, Sum)
+        });
+        let clock = manual_clock(&harness);
+        input.send_all([1, 2]);
+        harness.run_until_idle().unwrap();
+        clock.advance(Duration::from_millis(40));
+        input.send(4);
+        harness.run_until_idle().unwrap();
+        assert_eq!(output.take(), vec![3]);
+
+        clock.advance(Duration::from_millis(100));
+        harness.run_epoch().unwrap();
+        // The windows ending 60 and 90 ms after the first element.
+        assert_eq!(output.take(), vec![7, 4]);
+
+        clock.advance(Duration::from_millis(40));
+        harness.run_epoch().unwrap();
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn should_close_sessions_after_gap() {
+        let (mut harness, input, output) = harness(|context| {
+            let kind = WindowKind::Session {
+                gap: Duration::from_millis(50),
+            };
+            Window::new("window", context, kind, Min)
+        });
+        let clock = manual_clock(&harness);
+        input.send_all([4, 2]);
+        harness.run_until_idle().unwrap();
+        clock.advance(Duration::from_millis(10));
+        input.send(3);
+        harness.run_until_idle().unwrap();
+        assert!(output.is_empty());
+
+        clock.advance(Duration::from_millis(60));
+        input.send(8);
+        harness.run_until_idle().unwrap();
+        assert_eq!(output.take(), vec![2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_reject_empty_windows() {
+        let context = State::new(Context::new());
+        Window::<i32, i32>::new(
+            "window",
+            context,
+            WindowKind::TumblingCount { size: 0 },
+            Sum,
+        );
+    }
+
+    #[test]
+    fn should_construct_windows_from_registry() {
+        let mut registry = NodeRegistry::new();
+        registry.register_typed_node::<Window<i32, i32>>("Window");
+        let definition = |props: &str| {
+            FlowDefinition::from_json(&format!(
+                r#"{{
+                    "name": "window",
+                    "version": "1.0.0",
+                    "nodes": [{{ "name": "window", "kind": "Window", "props": {props} }}],
+                    "edges": []
+                }}"#
+            ))
+            .unwrap()
+        };
+
+        let context = State::new(Context::new());
+        let flow = definition(
+            r#"{ "window": { "type": "tumbling_count", "size": 2 }, "aggregate": "max" }"#,
+        )
+        .build(&registry, context.clone())
+        .unwrap();
+        let mut harness = FlowHarness::new(flow, context.clone());
+        let input = harness.feeder::<i32>("window", 0).unwrap();
+        let output = harness.probe::<i32>("window", 0).unwrap();
+        input.send_all([3, 1, 2, 5]);
+        harness.run_until_idle().unwrap();
+        assert_eq!(output.values(), vec![3, 5]);
+
+        let flow = definition(
+            r#"{ "window": { "type": "tumbling_count", "size": 2 }, "aggregate": "mean" }"#,
+        )
+        .build(&registry, context.clone())
+        .unwrap();
+        let mut harness = FlowHarness::new(flow, context.clone());
+        let input = harness.feeder::<i32>("window", 0).unwrap();
+        let output = harness.probe::<i32>("window", 0).unwrap();
+        input.send_all([1, 2, -4, -5, 3, 6]);
+        harness.run_until_idle().unwrap();
+        // Means are rounded half away from zero.
+        assert_eq!(output.values(), vec![2, -5, 5]);
+
+        let session =
+            definition(r#"{ "window": { "type": "session", "gap_ms": 0 }, "aggregate": "sum" }"#);
+        assert!(session.build(&registry, context).is_err());
+    }
+}
@@ -0,0 +1,207 @@
+#[cfg(test)]
+mod checkpoint {
+    use flowrs::{
+        checkpoint::{Checkpoint, CheckpointError},
+        connection::{connect, Input, Output},
+        definition::FlowDefinition,
+        executor::{Executor, MultiThreadedExecutor},
+        flow::Flow,
+        node::{Context, State},
+        registry::NodeRegistry,
+        scheduler::RoundRobinScheduler,
+        version::Version,
+    };
+    use serde_json::json;
+    use std::{sync::mpsc, thread, time::Duration};
+
+    use crate::sched::flow::CounterNode;
+
+    const COUNTERS: &str = r#"{
+        "name": "counters",
+        "version": "1.0.0",
+        "nodes": [
+            { "name": "counter_1", "kind": "Counter" },
+            { "name": "counter_2", "kind": "Counter" }
+        ],
+        "edges": [{
+            "from": { "node": "counter_1", "port": 0 },
+            "to": { "node": "counter_2", "port": 0 },
+            "type": "i32"
+        }]
+    }"#;
+
+    fn registry() -> NodeRegistry {
+        let mut registry = NodeRegistry::new();
+        registry.register_node("Counter", |name, context, _| {
+            Ok(CounterNode::new(name, context))
+        });
+        registry
+    }
+
+    fn input(flow: &Flow, node: &str) -> Input<i32> {
+        let ports = flow
+            .handle(flow.node_by_name(node).unwrap())
+            .unwrap()
+            .ports();
+        let input = ports.lock().unwrap().input_at(0);
+        input.downcast::<Input<i32>>().unwrap().as_ref().clone()
+    }
+
+    #[test]
+    fn should_restore_node_state_and_queues() {
+        let context = State::new(Context::new());
+        let definition = FlowDefinition::from_json(COUNTERS).unwrap();
+        let flow = definition.build(&registry(), context.clone()).unwrap();
+        flow.init_all().unwrap();
+        flow.ready_all().unwrap();
+
+        input(&flow, "counter_1").send(1).unwrap();
+        input(&flow, "counter_1").send(2).unwrap();
+        let counter_1 = flow.node_by_name("counter_1").unwrap();
+        flow.handle(counter_1).unwrap().update().unwrap();
+
+        let checkpoint = flow.checkpoint().unwrap();
+        assert_eq!(checkpoint.nodes["counter_1"], json!({ "count": 2 }));
+        assert_eq!(checkpoint.queues.len(), 1);
+        assert_eq!(checkpoint.queues[0].elements, vec![json!(1), json!(2)]);
+        // Taking a checkpoint keeps the queued elements.
+        assert_eq!(input(&flow, "counter_2").len(), 2);
+
+        let checkpoint = Checkpoint::from_json(&checkpoint.to_json().unwrap()).unwrap();
+        let restored = definition
+            .restore(&registry(), context, &checkpoint)
+            .unwrap();
+        assert_eq!(restored.checkpoint().unwrap(), checkpoint);
+        let counter_2 = input(&restored, "counter_2");
+        assert_eq!(counter_2.next_elem().unwrap(), 1);
+        assert_eq!(counter_2.next_elem().unwrap(), 2);
+    }
+
+    #[test]
+    fn should_reject_version_mismatch() {
+        let context = State::new(Context::new());
+        let definition = FlowDefinition::from_json(COUNTERS).unwrap();
+        let flow = definition.build(&registry(), context).unwrap();
+
+        let mut checkpoint = flow.checkpoint().unwrap();
+        checkpoint.version = Version::new(2, 0, 0);
+        let err = flow.restore(&checkpoint).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<CheckpointError>(),
+            Some(CheckpointError::VersionMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn should_reject_queued_elements_that_can_not_be_serialized() {
+        let context = State::new(Context::new());
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let counter_1 = flow
+            .add_node(CounterNode::new("counter_1", context.clone()))
+            .unwrap();
+        let counter_2 = flow
+            .add_node(CounterNode::new("counter_2", context))
+            .unwrap();
+        flow.connect::<i32>(counter_1, 0, counter_2, 0).unwrap();
+        assert!(flow.checkpoint().is_ok());
+
+        input(&flow, "counter_2").send(1).unwrap();
+        let err = flow.checkpoint().unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<CheckpointError>(),
+            Some(CheckpointError::NotSerializable { index: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn should_restore_inputs_with_opaque_and_serializable_edges() {
+        let build = |context: State<Context>| {
+            let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+            let counter_1 = flow
+                .add_node(CounterNode::new("counter_1", context.clone()))
+                .unwrap();
+            let counter_2 = flow
+                .add_node(CounterNode::new("counter_2", context.clone()))
+                .unwrap();
+            let counter_3 = flow
+                .add_node(CounterNode::new("counter_3", context))
+                .unwrap();
+            flow.connect::<i32>(counter_1, 0, counter_2, 0).unwrap();
+            flow.connect_serializable::<i32>(counter_3, 0, counter_2, 0)
+                .unwrap();
+            flow
+        };
+        let context = State::new(Context::new());
+        let flow = build(context.clone());
+        input(&flow, "counter_2").send(1).unwrap();
+        let checkpoint = flow.checkpoint().unwrap();
+        assert_eq!(checkpoint.queues[0].elements, vec![json!(1)]);
+
+        let restored = build(context);
+        restored.restore(&checkpoint).unwrap();
+        assert_eq!(input(&restored, "counter_2").next_elem().unwrap(), 1);
+    }
+
+    #[test]
+    fn should_reject_queued_elements_of_inputs_without_edges() {
+        let context = State::new(Context::new());
+        let definition = FlowDefinition::from_json(COUNTERS).unwrap();
+        let flow = definition.build(&registry(), context).unwrap();
+
+        // Elements fed from outside the flow have no codec to encode them.
+        input(&flow, "counter_1").send(1).unwrap();
+        let err = flow.checkpoint().unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<CheckpointError>(),
+            Some(CheckpointError::NotSerializable { index: 0, ref node }) if node == "counter_1"
+        ));
+    }
+
+    #[test]
+    fn should_checkpoint_running_flow() {
+        let (sender, receiver) = mpsc::channel();
+        let context = State::new(Context::new());
+        let definition = FlowDefinition::from_json(COUNTERS).unwrap();
+        let flow = definition.build(&registry(), context.clone()).unwrap();
+        let counter_1 = input(&flow, "counter_1");
+        let ports = flow
+            .handle(flow.node_by_name("counter_2").unwrap())
+            .unwrap()
+            .ports();
+        let output = ports.lock().unwrap().output_at(0);
+        connect(
+            output.downcast::<Output<i32>>().unwrap().as_ref().clone(),
+            Input::new(),
+        );
+
+        let thread_handle = thread::spawn(move || {
+            let mut executor = MultiThreadedExecutor::new(2, context);
+            let _ = sender.send((executor.controller(), executor.editor()));
+            executor.run(flow, RoundRobinScheduler::new())
+        });
+        let (controller, editor) = receiver.recv().unwrap();
+
+        counter_1.send(1).unwrap();
+        counter_1.send(2).unwrap();
+        // The checkpoint fails while the elements fed from outside are queued at counter_1.
+        let checkpoint = loop {
+            match editor.checkpoint().wait() {
+                Ok(checkpoint) if checkpoint.nodes["counter_2"] == json!({ "count": 2 }) => {
+                    break checkpoint;
+                }
+                Ok(_) => {}
+                Err(err) => assert!(matches!(
+                    err.downcast_ref::<CheckpointError>(),
+                    Some(CheckpointError::NotSerializable { .. })
+                )),
+            }
+            thread::sleep(Duration::from_millis(1));
+        };
+        assert_eq!(checkpoint.nodes["counter_1"], json!({ "count": 2 }));
+        assert!(checkpoint.queues.is_empty());
+
+        controller.lock().unwrap().cancel();
+        thread_handle.join().unwrap().unwrap();
+        assert!(editor.checkpoint().wait().is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use flowrs::connection::{Input, Output, RuntimeConnectable};
+use flowrs::node::{
+    ConfigError, Context, InitError, Node, ReadyError, ShutdownError, State, StateError,
+    UpdateError,
+};
+use flowrs_derive::Connectable;
+use serde_json::Value;
+
+use std::{any::Any, rc::Rc};
+
+/// Scales its input by a factor that can be reconfigured.
+#[derive(Connectable)]
+pub struct ScaleNode {
+    name: String,
+    pub factor: State<i32>,
+
+    #[input]
+    pub input_1: Input<i32>,
+    #[output]
+    pub output_1: Output<i32>,
+}
+
+impl ScaleNode {
+    pub fn new(name: &str, context: State<Context>) -> Self {
+        Self {
+            name: name.into(),
+            factor: State::new(1),
+            input_1: Input::new(),
+            output_1: Output::new(context),
+        }
+    }
+}
+
+impl Node for ScaleNode {
+    fn on_init(&self) -> Result<(), InitError> {
+        Ok(())
+    }
+
+    fn on_ready(&self) -> Result<(), ReadyError> {
+        Ok(())
+    }
+
+    fn on_shutdown(&self) -> Result<(), ShutdownError> {
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn update(&self) -> Result<(), UpdateError> {
+        let factor = *self.factor.0.lock().unwrap();
+        while let Ok(v) = self.input_1.next_elem() {
+            let _ = self.output_1.clone().send(v * factor);
+        }
+        Ok(())
+    }
+
+    fn export_state(&self) -> Result<Option<Value>, StateError> {
+        self.factor.to_value().map(Some)
+    }
+
+    fn import_state(&self, state: Value) -> Result<(), StateError> {
+        self.factor.load(state)
+    }
+
+    /// Applies the factor before checking it, so that rejected configs have to be rolled back.
+    fn on_config_change(&self, new_props: Value) -> Result<(), ConfigError> {
+        let factor = new_props["factor"].as_i64().unwrap_or_default() as i32;
+        *self.factor.0.lock().unwrap() = factor;
+        if factor == 0 {
+            return Err(ConfigError::Rejected {
+                node: self.name.clone(),
+                message: "The factor must not be zero.".into(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod editor {
+    use flowrs::{
+        connection::{connect, Input},
+        executor::{Executor, MultiThreadedExecutor},
+        flow::Flow,
+        node::{ConfigError, Context, State},
+        scheduler::RoundRobinScheduler,
+        version::Version,
+    };
+    use serde_json::json;
+    use std::{sync::mpsc, thread};
+
+    use super::ScaleNode;
+    use crate::sched::sched::DummyNode;
+
+    fn sink(name: &str, context: State<Context>, err_on_init: bool) -> DummyNode {
+        let node = DummyNode::new(name, context, err_on_init);
+        connect(node.output_1.clone(), Input::new());
+        node
+    }
+
+    #[test]
+    fn should_modify_running_flow() {
+        let (sender, receiver) = mpsc::channel();
+        let context = State::new(Context::new());
+
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let id1 = flow
+            .add_node(DummyNode::new("node_1", context.clone(), false))
+            .unwrap();
+        let id2 = flow
+            .add_node(sink("node_2", context.clone(), false))
+            .unwrap();
+        flow.connect::<i32>(id1, 0, id2, 0).unwrap();
+
+        let executor_context = context.clone();
+        let thread_handle = thread::spawn(move || {
+            let mut executor = MultiThreadedExecutor::new(2, executor_context);
+            let _ = sender.send((executor.controller(), executor.editor()));
+            executor.run(flow, RoundRobinScheduler::new())
+        });
+        let (controller, editor) = receiver.recv().unwrap();
+
+        editor
+            .add_node(sink("node_3", context.clone(), false))
+            .wait()
+            .unwrap();
+        assert!(editor
+            .add_node(sink("node_3", context.clone(), false))
+            .wait()
+            .is_err());
+
+        // Rewire node_1 from node_2 to node_3.
+        assert!(editor
+            .connect::<i32>("node_1", 0, "node_3", 0)
+            .wait()
+            .is_err());
+        editor.disconnect("node_1", 0).wait().unwrap();
+        assert!(editor.disconnect("node_1", 0).wait().is_err());
+        assert!(editor
+            .connect::<String>("node_1", 0, "node_3", 0)
+            .wait()
+            .is_err());
+        editor
+            .connect::<i32>("node_1", 0, "node_3", 0)
+            .wait()
+            .unwrap();
+
+        // A node failing on init is not added.
+        assert!(editor
+            .add_node(sink("node_4", context, true))
+            .wait()
+            .is_err());
+        assert!(editor.remove_node("node_4").wait().is_err());
+
+        editor.remove_node("node_2").wait().unwrap();
+        assert!(editor.remove_node("node_2").wait().is_err());
+
+        controller.lock().unwrap().cancel();
+        thread_handle.join().unwrap().unwrap();
+
+        // Nobody applies changes once the executor stopped.
+        assert!(editor.remove_node("node_1").wait().is_err());
+    }
+
+    #[test]
+    fn should_reconfigure_running_node() {
+        let (sender, receiver) = mpsc::channel();
+        let context = State::new(Context::new());
+
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let scale = ScaleNode::new("scale", context.clone());
+        let factor = scale.factor.clone();
+        let id1 = flow.add_node(scale).unwrap();
+        let id2 = flow
+            .add_node(sink("node_2", context.clone(), false))
+            .unwrap();
+        flow.connect::<i32>(id1, 0, id2, 0).unwrap();
+
+        let thread_handle = thread::spawn(move || {
+            let mut executor = MultiThreadedExecutor::new(2, context);
+            let _ = sender.send((executor.controller(), executor.editor()));
+            executor.run(flow, RoundRobinScheduler::new())
+        });
+        let (controller, editor) = receiver.recv().unwrap();
+
+        // The props the node was added with are unknown, so its state is rolled back instead.
+        assert!(editor
+            .configure("scale", json!({ "factor": 0 }))
+            .wait()
+            .is_err());
+        assert_eq!(*factor.0.lock().unwrap(), 1);
+
+        editor.configure("scale", json!({ "factor": 2 })).wait().unwrap();
+        assert_eq!(*factor.0.lock().unwrap(), 2);
+
+        // The rejected factor is rolled back.
+        let err = editor
+            .configure("scale", json!({ "factor": 0 }))
+            .wait()
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ConfigError>(),
+            Some(ConfigError::Rejected { .. })
+        ));
+        assert_eq!(*factor.0.lock().unwrap(), 2);
+
+        let err = editor.configure("node_2", json!({})).wait().unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ConfigError>(),
+            Some(ConfigError::Unsupported { .. })
+        ));
+        assert!(editor.configure("node_3", json!({})).wait().is_err());
+
+        controller.lock().unwrap().cancel();
+        thread_handle.join().unwrap().unwrap();
+    }
+}
@@ -0,0 +1,136 @@
+use flowrs::connection::{Input, RuntimeConnectable};
+use flowrs::node::{Context, Node, State, UpdateError};
+use flowrs_derive::Connectable;
+
+use std::{
+    any::Any,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Asks for a single update `delay` after its first update and records the time of every update
+/// on the clock of its context.
+#[derive(Connectable)]
+pub struct TimerNode {
+    name: String,
+    delay: Duration,
+    context: State<Context>,
+    updates: Arc<Mutex<Vec<Instant>>>,
+
+    #[input]
+    pub input_1: Input<i32>,
+}
+
+impl TimerNode {
+    pub fn new(name: &str, context: State<Context>, delay: Duration) -> Self {
+        Self {
+            name: name.into(),
+            delay,
+            context,
+            updates: Arc::new(Mutex::new(Vec::new())),
+            input_1: Input::new(),
+        }
+    }
+
+    pub fn updates(&self) -> Arc<Mutex<Vec<Instant>>> {
+        self.updates.clone()
+    }
+}
+
+impl Node for TimerNode {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn update(&self) -> Result<(), UpdateError> {
+        let context = self.context.0.lock().unwrap();
+        let mut updates = self.updates.lock().unwrap();
+        if updates.is_empty() {
+            context.request_update_at(context.now() + self.delay);
+        }
+        updates.push(context.now());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod executor {
+    use flowrs::{
+        executor::{ExecutionController, Executor, MultiThreadedExecutor},
+        flow::Flow,
+        node::{Clock, Context, State},
+        scheduler::RoundRobinScheduler,
+        version::Version,
+    };
+    use std::{
+        sync::{Arc, Mutex},
+        thread::{self, JoinHandle},
+        time::Duration,
+    };
+
+    use super::TimerNode;
+
+    type Running = (
+        Arc<Mutex<ExecutionController>>,
+        JoinHandle<anyhow::Result<()>>,
+    );
+
+    fn run(context: State<Context>, timer: TimerNode) -> Running {
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        flow.add_node(timer).unwrap();
+        let mut executor = MultiThreadedExecutor::new(1, context);
+        let controller = executor.controller();
+        let handle = thread::spawn(move || executor.run(flow, RoundRobinScheduler::new()));
+        (controller, handle)
+    }
+
+    #[test]
+    fn should_sleep_until_requested_update() {
+        let context = State::new(Context::new());
+        let timer = TimerNode::new("timer", context.clone(), Duration::from_millis(200));
+        let updates = timer.updates();
+        let (controller, handle) = run(context, timer);
+
+        thread::sleep(Duration::from_millis(100));
+        // Nothing changed, so the executor sleeps instead of running epochs. The request itself
+        // wakes it up once.
+        assert!(controller.lock().unwrap().epoch() <= 2);
+
+        thread::sleep(Duration::from_millis(300));
+        let epochs = controller.lock().unwrap().epoch();
+        controller.lock().unwrap().cancel();
+        handle.join().unwrap().unwrap();
+        let updates = updates.lock().unwrap();
+        assert!(epochs <= 3);
+        assert!(*updates.last().unwrap() - updates[0] >= Duration::from_millis(200));
+    }
+
+    #[test]
+    fn should_take_requested_updates_from_the_context_clock() {
+        let clock = Clock::manual();
+        let mut context = Context::new();
+        context.set_clock(clock.clone());
+        let context = State::new(context);
+        let timer = TimerNode::new("timer", context.clone(), Duration::from_millis(50));
+        let updates = timer.updates();
+        let (controller, handle) = run(context, timer);
+
+        // The deadline passed in real time, but not on the manual clock.
+        thread::sleep(Duration::from_millis(200));
+        assert!(controller.lock().unwrap().epoch() <= 2);
+        let first = updates.lock().unwrap()[0];
+        assert!(updates.lock().unwrap().iter().all(|at| *at == first));
+
+        clock.advance(Duration::from_millis(50));
+        thread::sleep(Duration::from_millis(200));
+        let epochs = controller.lock().unwrap().epoch();
+        controller.lock().unwrap().cancel();
+        handle.join().unwrap().unwrap();
+        assert!(epochs <= 3);
+        assert_eq!(
+            *updates.lock().unwrap().last().unwrap(),
+            first + Duration::from_millis(50)
+        );
+    }
+}
@@ -0,0 +1,527 @@
+use flowrs::connection::{Input, Output, RuntimeConnectable};
+use flowrs::node::{
+    ConfigError, Context, InitError, Node, ReadyError, ShutdownError, State, StateError,
+    UpdateError,
+};
+use flowrs_derive::Connectable;
+use serde_json::{json, Value};
+
+use std::{any::Any, rc::Rc};
+
+/// Forwards its input and counts the forwarded elements.
+#[derive(Connectable)]
+pub struct CounterNode {
+    name: String,
+    count: State<u64>,
+
+    #[input]
+    pub input_1: Input<i32>,
+    #[output]
+    pub output_1: Output<i32>,
+}
+
+impl CounterNode {
+    pub fn new(name: &str, context: State<Context>) -> Self {
+        Self {
+            name: name.into(),
+            count: State::new(0),
+            input_1: Input::new(),
+            output_1: Output::new(context),
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        *self.count.0.lock().unwrap()
+    }
+
+    pub fn set_count(&self, count: u64) {
+        *self.count.0.lock().unwrap() = count;
+    }
+}
+
+impl Node for CounterNode {
+    fn on_init(&self) -> Result<(), InitError> {
+        Ok(())
+    }
+
+    fn on_ready(&self) -> Result<(), ReadyError> {
+        Ok(())
+    }
+
+    fn on_shutdown(&self) -> Result<(), ShutdownError> {
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn update(&self) -> Result<(), UpdateError> {
+        while let Ok(v) = self.input_1.next_elem() {
+            *self.count.0.lock().unwrap() += 1;
+            let _ = self.output_1.clone().send(v);
+        }
+        Ok(())
+    }
+
+    fn export_state(&self) -> Result<Option<Value>, StateError> {
+        Ok(Some(json!({ "count": self.count() })))
+    }
+
+    fn import_state(&self, state: Value) -> Result<(), StateError> {
+        let count = state["count"].as_u64().ok_or(StateError::Incompatible {
+            node: self.name.clone(),
+            message: "Missing count.".into(),
+        })?;
+        *self.count.0.lock().unwrap() = count;
+        Ok(())
+    }
+}
+
+/// Rejects any imported state and fails to shut down. Does not support config changes, counting
+/// the attempts.
+#[derive(Connectable)]
+pub struct StatelessNode {
+    name: String,
+    pub config_changes: State<u32>,
+
+    #[input]
+    pub input_1: Input<i32>,
+    #[output]
+    pub output_1: Output<i32>,
+}
+
+impl StatelessNode {
+    pub fn new(name: &str, context: State<Context>) -> Self {
+        Self {
+            name: name.into(),
+            config_changes: State::new(0),
+            input_1: Input::new(),
+            output_1: Output::new(context),
+        }
+    }
+}
+
+impl Node for StatelessNode {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn update(&self) -> Result<(), UpdateError> {
+        Ok(())
+    }
+
+    fn import_state(&self, _state: Value) -> Result<(), StateError> {
+        Err(StateError::Incompatible {
+            node: self.name.clone(),
+            message: "The node has no state.".into(),
+        })
+    }
+
+    fn on_shutdown(&self) -> Result<(), ShutdownError> {
+        Err(ShutdownError::Other(anyhow::anyhow!("Failed.")))
+    }
+
+    fn on_config_change(&self, _new_props: Value) -> Result<(), ConfigError> {
+        *self.config_changes.0.lock().unwrap() += 1;
+        Err(ConfigError::Unsupported {
+            node: self.name.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod flow {
+    use flowrs::{
+        connection::{connect, Edge},
+        definition::DefinitionError,
+        flow::{Flow, FlowError, Port, PortKind},
+        lifecycle::{LifecycleError, NodeLifecycle},
+        node::{ConfigError, Context, InitError, State, StateError},
+        nodes::std::Constant,
+        registry::NodeRegistry,
+        version::Version,
+    };
+    use serde_json::json;
+
+    use super::{CounterNode, StatelessNode};
+    use crate::sched::sched::DummyNode;
+
+    #[test]
+    fn should_track_node_lifecycle() {
+        let context = State::new(Context::new());
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let id = flow
+            .add_node(DummyNode::new("node_1", context, false))
+            .unwrap();
+
+        assert_eq!(flow.node_state(id), Some(NodeLifecycle::Created));
+        flow.init_all().unwrap();
+        assert_eq!(flow.node_state(id), Some(NodeLifecycle::Initialized));
+        flow.ready_all().unwrap();
+        assert_eq!(flow.node_state(id), Some(NodeLifecycle::Ready));
+        flow.handle(id).unwrap().update().unwrap();
+        assert_eq!(flow.node_state(id), Some(NodeLifecycle::Running));
+        flow.shutdown_all().unwrap();
+        assert_eq!(flow.node_state(id), Some(NodeLifecycle::ShutDown));
+    }
+
+    #[test]
+    fn should_shut_down_every_initialized_node() {
+        let context = State::new(Context::new());
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let ok = flow
+            .add_node(DummyNode::new("ok", context.clone(), false))
+            .unwrap();
+        let stateless_1 = flow
+            .add_node(StatelessNode::new("stateless_1", context.clone()))
+            .unwrap();
+        let stateless_2 = flow
+            .add_node(StatelessNode::new("stateless_2", context.clone()))
+            .unwrap();
+        let failing = flow
+            .add_node(DummyNode::new("failing", context.clone(), true))
+            .unwrap();
+        let created = flow
+            .add_node(DummyNode::new("created", context, false))
+            .unwrap();
+        assert!(flow.init_all().is_err());
+
+        let err = flow.shutdown_all().unwrap_err();
+        match err.downcast_ref::<FlowError>() {
+            Some(FlowError::ShutdownFailed(errors)) => assert_eq!(errors.len(), 2),
+            _ => panic!("Unexpected error: {:?}", err),
+        }
+        assert!(err.to_string().contains("stateless_2"));
+        assert_eq!(flow.node_state(ok), Some(NodeLifecycle::ShutDown));
+        assert_eq!(flow.node_state(stateless_1), Some(NodeLifecycle::Failed));
+        assert_eq!(flow.node_state(stateless_2), Some(NodeLifecycle::Failed));
+        assert_eq!(flow.node_state(failing), Some(NodeLifecycle::ShutDown));
+        assert_eq!(flow.node_state(created), Some(NodeLifecycle::Created));
+    }
+
+    #[test]
+    fn should_reject_update_before_ready() {
+        let context = State::new(Context::new());
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let id = flow
+            .add_node(DummyNode::new("node_1", context, false))
+            .unwrap();
+        flow.init_all().unwrap();
+
+        let err = flow.handle(id).unwrap().update().unwrap_err();
+        let err = err.downcast_ref::<LifecycleError>().unwrap();
+        assert_eq!(err.from, NodeLifecycle::Initialized);
+        assert_eq!(err.to, NodeLifecycle::Running);
+        assert_eq!(flow.node_state(id), Some(NodeLifecycle::Initialized));
+    }
+
+    #[test]
+    fn should_fail_node_on_init_error() {
+        let context = State::new(Context::new());
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let id = flow
+            .add_node(DummyNode::new("node_1", context, true))
+            .unwrap();
+
+        assert!(flow.init_all().is_err());
+        assert_eq!(flow.node_state(id), Some(NodeLifecycle::Failed));
+        assert!(flow.ready_all().is_err());
+        assert_eq!(flow.node_state(id), Some(NodeLifecycle::Failed));
+    }
+
+    #[test]
+    fn should_reject_duplicate_names() {
+        let context = State::new(Context::new());
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        flow.add_node(DummyNode::new("node_1", context.clone(), false))
+            .unwrap();
+
+        match flow.add_node(DummyNode::new("node_1", context, false)) {
+            Err(FlowError::DuplicateName(name)) => assert_eq!(name, "node_1"),
+            _ => panic!("Duplicate node name was accepted."),
+        }
+        assert_eq!(flow.num_nodes(), 1);
+    }
+
+    #[test]
+    fn should_keep_ids_stable_on_removal() {
+        let context = State::new(Context::new());
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let id1 = flow
+            .add_node(DummyNode::new("node_1", context.clone(), false))
+            .unwrap();
+        let id2 = flow
+            .add_node(DummyNode::new("node_2", context.clone(), false))
+            .unwrap();
+        let id3 = flow
+            .add_node(DummyNode::new("node_3", context, false))
+            .unwrap();
+
+        let removed = flow.remove_node(id2).unwrap();
+        assert_eq!(removed.name(), "node_2");
+        assert!(flow.remove_node(id2).is_err());
+
+        assert_eq!(flow.node_by_name("node_1"), Some(id1));
+        assert_eq!(flow.node_by_name("node_2"), None);
+        assert_eq!(flow.node_by_name("node_3"), Some(id3));
+        assert_eq!(flow.node_ids().collect::<Vec<_>>(), vec![id1, id3]);
+        assert_eq!(
+            flow.iter().map(|h| h.name()).collect::<Vec<_>>(),
+            vec!["node_1", "node_3"]
+        );
+    }
+
+    #[test]
+    fn should_record_connections() {
+        let context = State::new(Context::new());
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let n1 = DummyNode::new("node_1", context.clone(), false);
+        let output = n1.output_1.clone();
+        let id1 = flow.add_node(n1).unwrap();
+        let id2 = flow
+            .add_node(DummyNode::new("node_2", context.clone(), false))
+            .unwrap();
+        let id3 = flow
+            .add_node(DummyNode::new("node_3", context, false))
+            .unwrap();
+
+        flow.connect::<i32>(id1, 0, id2, 0).unwrap();
+        flow.connect::<i32>(id2, 0, id3, 0).unwrap();
+
+        assert_eq!(flow.edges().len(), 2);
+        let edge = &flow.edges()[0];
+        assert_eq!(
+            edge.from(),
+            Port {
+                node: id1,
+                index: 0
+            }
+        );
+        assert_eq!(
+            edge.to(),
+            Port {
+                node: id2,
+                index: 0
+            }
+        );
+        assert_eq!(edge.type_name(), "i32");
+
+        assert_eq!(flow.predecessors(id2), vec![id1]);
+        assert_eq!(flow.successors(id2), vec![id3]);
+        assert_eq!(flow.sources(), vec![id1]);
+        assert_eq!(flow.sinks(), vec![id3]);
+        assert!(output.is_connected());
+
+        flow.remove_node(id2).unwrap();
+        assert!(flow.edges().is_empty());
+        assert!(!output.is_connected());
+    }
+
+    #[test]
+    fn should_reject_invalid_connections() {
+        let context = State::new(Context::new());
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let id1 = flow
+            .add_node(DummyNode::new("node_1", context.clone(), false))
+            .unwrap();
+        let id2 = flow
+            .add_node(DummyNode::new("node_2", context, false))
+            .unwrap();
+
+        assert!(matches!(
+            flow.connect::<String>(id1, 0, id2, 0),
+            Err(FlowError::PortTypeMismatch {
+                kind: PortKind::Output,
+                ..
+            })
+        ));
+        assert!(matches!(
+            flow.connect::<i32>(id1, 1, id2, 0),
+            Err(FlowError::UnknownPort {
+                kind: PortKind::Output,
+                index: 1,
+                ..
+            })
+        ));
+        flow.connect::<i32>(id1, 0, id2, 0).unwrap();
+        assert!(matches!(
+            flow.connect::<i32>(id1, 0, id2, 0),
+            Err(FlowError::AlreadyConnected { index: 0, .. })
+        ));
+        assert_eq!(flow.edges().len(), 1);
+    }
+
+    #[test]
+    fn should_replace_node_preserving_connections() {
+        let context = State::new(Context::new());
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let n1 = DummyNode::new("node_1", context.clone(), false);
+        let upstream = n1.output_1.clone();
+        let counter = CounterNode::new("counter", context.clone());
+        let old_input = counter.input_1.clone();
+        let mock_output = Edge::new();
+        connect(counter.output_1.clone(), mock_output.clone());
+
+        let id1 = flow.add_node(n1).unwrap();
+        let id2 = flow.add_node(counter).unwrap();
+        flow.connect::<i32>(id1, 0, id2, 0).unwrap();
+        flow.init_all().unwrap();
+        flow.ready_all().unwrap();
+
+        upstream.clone().send(1).unwrap();
+        upstream.clone().send(2).unwrap();
+        flow.handle(id2).unwrap().update().unwrap();
+        upstream.clone().send(3).unwrap();
+        upstream.clone().send(4).unwrap();
+
+        let replacement = CounterNode::new("counter", context);
+        let new_input = replacement.input_1.clone();
+        let count = replacement.count.clone();
+        let old = flow.replace_node(id2, replacement).unwrap();
+
+        assert_eq!(old.state(), NodeLifecycle::ShutDown);
+        assert_eq!(flow.node_state(id2), Some(NodeLifecycle::Ready));
+        assert_eq!(*count.0.lock().unwrap(), 2);
+        assert_eq!(old_input.len(), 0);
+        assert_eq!(new_input.len(), 2);
+        assert_eq!(flow.edges().len(), 1);
+
+        upstream.clone().send(5).unwrap();
+        flow.handle(id2).unwrap().update().unwrap();
+        assert_eq!(*count.0.lock().unwrap(), 5);
+        let received: Vec<i32> = (0..5).map(|_| mock_output.next_elem().unwrap()).collect();
+        assert_eq!(received, vec![1, 2, 3, 4, 5]);
+
+        // Clones of the old input kept outside of the flow still send to the old queue.
+        old_input.send(6).unwrap();
+        flow.handle(id2).unwrap().update().unwrap();
+        assert_eq!(old_input.len(), 1);
+        assert!(mock_output.next_elem().is_err());
+    }
+
+    #[test]
+    fn should_keep_old_node_if_replacement_fails() {
+        let context = State::new(Context::new());
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let n1 = DummyNode::new("node_1", context.clone(), false);
+        let upstream = n1.output_1.clone();
+        let counter = CounterNode::new("counter", context.clone());
+        let count = counter.count.clone();
+        connect(counter.output_1.clone(), Edge::new());
+
+        let id1 = flow.add_node(n1).unwrap();
+        let id2 = flow.add_node(counter).unwrap();
+        flow.connect::<i32>(id1, 0, id2, 0).unwrap();
+        flow.init_all().unwrap();
+        flow.ready_all().unwrap();
+
+        let err = flow
+            .replace_node(id2, StatelessNode::new("counter", context.clone()))
+            .unwrap_err();
+        assert!(err.downcast_ref::<StateError>().is_some());
+        let err = flow
+            .replace_node(id2, DummyNode::new("counter", context, true))
+            .unwrap_err();
+        assert!(err.downcast_ref::<InitError>().is_some());
+
+        assert_eq!(flow.node_state(id2), Some(NodeLifecycle::Ready));
+        upstream.clone().send(1).unwrap();
+        flow.handle(id2).unwrap().update().unwrap();
+        assert_eq!(*count.0.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn should_reject_incompatible_replacement() {
+        let context = State::new(Context::new());
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let id1 = flow
+            .add_node(DummyNode::new("node_1", context.clone(), false))
+            .unwrap();
+        let id2 = flow
+            .add_node(DummyNode::new("node_2", context.clone(), false))
+            .unwrap();
+        flow.connect::<i32>(id1, 0, id2, 0).unwrap();
+
+        let err = flow
+            .replace_node(id2, DummyNode::new("node_1", context, false))
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<FlowError>(),
+            Some(FlowError::DuplicateName(_))
+        ));
+        assert_eq!(flow.node_state(id2), Some(NodeLifecycle::Created));
+    }
+
+    #[test]
+    fn should_not_roll_back_unsupported_config_changes() {
+        let changes = State::new(0);
+        let mut registry = NodeRegistry::new();
+        let node_changes = changes.clone();
+        registry.register_node("Stateless", move |name, context, _| {
+            let mut node = StatelessNode::new(name, context);
+            node.config_changes = node_changes.clone();
+            Ok(node)
+        });
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let context = State::new(Context::new());
+        let id = registry
+            .add_node(&mut flow, "Stateless", "node_1", context, json!({ "a": 1 }))
+            .unwrap();
+        flow.init_all().unwrap();
+        flow.ready_all().unwrap();
+
+        let err = flow.configure(id, json!({ "a": 2 })).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ConfigError>(),
+            Some(ConfigError::Unsupported { .. })
+        ));
+        assert_eq!(*changes.0.lock().unwrap(), 1);
+        assert_eq!(flow.handle(id).unwrap().config(), json!({ "a": 1 }));
+    }
+
+    #[test]
+    fn should_validate_config_changes_of_ready_nodes() {
+        let mut registry = NodeRegistry::new();
+        registry.register_typed_node::<Constant<i32>>("Constant");
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let context = State::new(Context::new());
+        let id = registry
+            .add_node(
+                &mut flow,
+                "Constant",
+                "node_1",
+                context,
+                json!({ "value": 1 }),
+            )
+            .unwrap();
+
+        let err = flow.configure(id, json!({ "value": 2 })).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<FlowError>(),
+            Some(FlowError::NotConfigurable {
+                state: NodeLifecycle::Created,
+                ..
+            })
+        ));
+
+        flow.init_all().unwrap();
+        flow.ready_all().unwrap();
+        let err = flow.configure(id, json!({ "value": "2" })).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<DefinitionError>(),
+            Some(DefinitionError::InvalidProps { ref field, .. }) if field == "/value"
+        ));
+        assert_eq!(flow.handle(id).unwrap().config(), json!({ "value": 1 }));
+
+        flow.shutdown_all().unwrap();
+        let err = flow.configure(id, json!({ "value": 2 })).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<FlowError>(),
+            Some(FlowError::NotConfigurable {
+                state: NodeLifecycle::ShutDown,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod metrics {
+    use flowrs::{
+        connection::{connect, Input},
+        executor::{Executor, MultiThreadedExecutor},
+        flow::Flow,
+        function::FnSink,
+        metrics::Metrics,
+        node::{Context, State},
+        scheduler::RoundRobinScheduler,
+        version::Version,
+    };
+    use std::{sync::mpsc, thread, time::Duration};
+
+    use crate::sched::sched::DummyNode;
+
+    fn flow(context: State<Context>) -> Flow {
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let id1 = flow
+            .add_node(DummyNode::new("node_1", context.clone(), false))
+            .unwrap();
+        let node = DummyNode::new("node_\"2\"", context, false);
+        connect(node.output_1.clone(), Input::new());
+        let id2 = flow.add_node(node).unwrap();
+        flow.connect::<i32>(id1, 0, id2, 0).unwrap();
+        flow
+    }
+
+    #[test]
+    fn should_export_prometheus_text() {
+        let flow = flow(State::new(Context::new()));
+        let metrics = Metrics::new();
+        metrics.record_update("node_1", Duration::from_millis(10), false);
+        metrics.record_update("node_1", Duration::from_millis(30), true);
+        metrics.record_epoch(&flow);
+
+        let snapshot = metrics.snapshot();
+        let node = snapshot.nodes["node_1"];
+        assert_eq!((node.updates, node.errors), (2, 1));
+        assert_eq!(node.mean_latency(), Duration::from_millis(20));
+        assert_eq!(node.max_latency, Duration::from_millis(30));
+
+        let mut text = Vec::new();
+        snapshot.write_prometheus(&mut text).unwrap();
+        let text = String::from_utf8(text).unwrap();
+        assert!(text.contains("# TYPE flowrs_node_updates_total counter\n"));
+        assert!(text.contains("flowrs_epochs_total 1\n"));
+        assert!(text.contains("flowrs_node_updates_total{node=\"node_1\"} 2\n"));
+        assert!(text.contains("flowrs_node_update_errors_total{node=\"node_1\"} 1\n"));
+        assert!(text.contains("flowrs_node_update_seconds_max{node=\"node_1\"} 0.03\n"));
+        assert!(text.contains(
+            "flowrs_edge_queue_depth{from=\"node_1\",from_port=\"0\",to=\"node_\\\"2\\\"\",to_port=\"0\"} 0\n"
+        ));
+    }
+
+    #[test]
+    fn should_collect_metrics_while_running() {
+        let (sender, receiver) = mpsc::channel();
+        let context = State::new(Context::new());
+        let flow = flow(context.clone());
+
+        let thread_handle = thread::spawn(move || {
+            let mut executor = MultiThreadedExecutor::new(2, context);
+            let _ = sender.send((executor.controller(), executor.editor(), executor.metrics()));
+            executor.run(flow, RoundRobinScheduler::new())
+        });
+        let (controller, editor, metrics) = receiver.recv().unwrap();
+
+        // Changes are applied at the start of an epoch.
+        editor.disconnect("node_1", 0).wait().unwrap();
+        controller.lock().unwrap().cancel();
+        thread_handle.join().unwrap().unwrap();
+
+        let snapshot = metrics.snapshot();
+        assert!(snapshot.epochs >= 1);
+        assert_eq!(controller.lock().unwrap().epoch(), snapshot.epochs);
+        assert!(snapshot.edges.is_empty());
+    }
+
+    #[test]
+    fn should_record_epochs_once_their_updates_are_done() {
+        let context = State::new(Context::new());
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let source = flow
+            .add_node(DummyNode::new("source", context.clone(), false))
+            .unwrap();
+        let slow = FnSink::new("slow", |_: i32| thread::sleep(Duration::from_millis(100)));
+        slow.input.send(1).unwrap();
+        let slow = flow.add_node(slow).unwrap();
+        flow.connect::<i32>(source, 0, slow, 0).unwrap();
+
+        let mut executor = MultiThreadedExecutor::new(2, context);
+        let controller = executor.controller();
+        let metrics = executor.metrics();
+        let thread_handle = thread::spawn(move || executor.run(flow, RoundRobinScheduler::new()));
+        let snapshot = loop {
+            let snapshot = metrics.snapshot();
+            if snapshot.epochs > 0 {
+                break snapshot;
+            }
+            thread::sleep(Duration::from_millis(1));
+        };
+        controller.lock().unwrap().cancel();
+        thread_handle.join().unwrap().unwrap();
+
+        assert_eq!(snapshot.nodes["slow"].updates, 1);
+        assert_eq!(snapshot.edges[0].queue_depth, 0);
+    }
+}
@@ -0,0 +1,168 @@
+#[cfg(test)]
+mod migration {
+    use anyhow::anyhow;
+    use flowrs::{
+        definition::FlowDefinition,
+        migration::{Migration, MigrationError},
+        node::{Context, State},
+        registry::NodeRegistry,
+        version::Version,
+    };
+    use serde_json::json;
+
+    use crate::sched::flow::CounterNode;
+
+    const COUNTERS: &str = r#"{
+        "name": "counters",
+        "version": "1.0.0",
+        "library": "1.0.0",
+        "nodes": [{ "name": "counter_1", "kind": "LegacyCounter" }]
+    }"#;
+
+    fn v(s: &str) -> Version {
+        s.parse().unwrap()
+    }
+
+    fn registry() -> NodeRegistry {
+        let mut registry = NodeRegistry::new();
+        registry.set_library_version(v("2.0.0"));
+        registry.register_node("Counter", |name, context, _| {
+            Ok(CounterNode::new(name, context))
+        });
+        register_migrations(&mut registry);
+        registry
+    }
+
+    fn register_migrations(registry: &mut NodeRegistry) {
+        registry.register_migration(
+            "Counter",
+            Migration::new(v("2.0.0"), "Add props.", |definition, name| {
+                let node = definition.nodes.iter_mut().find(|n| n.name == name);
+                node.unwrap().props = json!({ "start": 0 });
+                Ok(())
+            }),
+        );
+        registry.register_migration(
+            "LegacyCounter",
+            Migration::new(v("1.1.0"), "Rename LegacyCounter.", |definition, name| {
+                let node = definition.nodes.iter_mut().find(|n| n.name == name);
+                node.unwrap().kind = "Counter".into();
+                Ok(())
+            }),
+        );
+    }
+
+    #[test]
+    fn should_apply_migrations_in_order_on_load() {
+        let registry = registry();
+        let (definition, report) = FlowDefinition::load(COUNTERS, &registry).unwrap();
+        assert_eq!(definition.library, Some(v("2.0.0")));
+        assert_eq!(definition.nodes[0].kind, "Counter");
+        assert_eq!(definition.nodes[0].props, json!({ "start": 0 }));
+
+        let applied: Vec<_> = report
+            .applied
+            .iter()
+            .map(|m| (m.kind.as_str(), m.version.to_string()))
+            .collect();
+        assert_eq!(
+            applied,
+            vec![
+                ("LegacyCounter", "1.1.0".to_string()),
+                ("Counter", "2.0.0".to_string())
+            ]
+        );
+        assert!(report
+            .to_string()
+            .contains("counter_1 (LegacyCounter) 1.1.0: Rename LegacyCounter."));
+
+        let flow = definition
+            .build(&registry, State::new(Context::new()))
+            .unwrap();
+        assert_eq!(flow.version(), &v("1.0.0"));
+
+        // Up to date definitions are left alone.
+        let mut definition = definition;
+        assert!(definition.migrate(&registry).unwrap().is_empty());
+    }
+
+    #[test]
+    fn should_only_apply_migrations_newer_than_the_definition() {
+        let mut definition = FlowDefinition::from_json(COUNTERS).unwrap();
+        definition.library = Some(v("1.1.0"));
+        let report = definition.migrate(&registry()).unwrap();
+        assert_eq!(report.applied.len(), 0);
+        assert_eq!(definition.nodes[0].kind, "LegacyCounter");
+
+        // Definitions without a library version get all migrations.
+        let mut definition = FlowDefinition::from_json(COUNTERS).unwrap();
+        definition.library = None;
+        let report = definition.migrate(&registry()).unwrap();
+        assert_eq!(report.applied.len(), 2);
+    }
+
+    #[test]
+    fn should_reject_definition_for_newer_library() {
+        let mut definition = FlowDefinition::from_json(COUNTERS).unwrap();
+        definition.library = Some(v("2.1.0"));
+        let err = definition.migrate(&registry()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<MigrationError>(),
+            Some(MigrationError::NewerLibrary { .. })
+        ));
+    }
+
+    #[test]
+    fn should_leave_definition_unchanged_on_failure() {
+        let mut registry = registry();
+        registry.set_library_version(v("3.0.0"));
+        registry.register_migration(
+            "Counter",
+            Migration::new(v("3.0.0"), "Fail.", |_, _| Err(anyhow!("Failed."))),
+        );
+        let mut definition = FlowDefinition::from_json(COUNTERS).unwrap();
+        let original = definition.clone();
+        assert!(definition.migrate(&registry).is_err());
+        assert_eq!(definition, original);
+    }
+
+    #[test]
+    fn should_migrate_subflows_and_built_definitions() {
+        let outer = FlowDefinition::from_json(
+            r#"{
+                "name": "outer",
+                "version": "1.0.0",
+                "nodes": [{ "name": "counters", "kind": "Counters" }]
+            }"#,
+        )
+        .unwrap();
+
+        let mut registry = registry();
+        let report = registry
+            .register_subflow("Counters", FlowDefinition::from_json(COUNTERS).unwrap())
+            .unwrap();
+        assert_eq!(report.applied.len(), 2);
+        assert!(outer.build(&registry, State::new(Context::new())).is_ok());
+
+        // Migrations registered after the subflow are applied when it is built.
+        let mut registry = NodeRegistry::new();
+        registry.set_library_version(v("2.0.0"));
+        registry.register_node("Counter", |name, context, _| {
+            Ok(CounterNode::new(name, context))
+        });
+        let report = registry
+            .register_subflow("Counters", FlowDefinition::from_json(COUNTERS).unwrap())
+            .unwrap();
+        assert!(report.is_empty());
+        assert!(outer.build(&registry, State::new(Context::new())).is_err());
+        register_migrations(&mut registry);
+        assert!(outer.build(&registry, State::new(Context::new())).is_ok());
+
+        // Definitions that were not loaded are migrated when built.
+        let flow = FlowDefinition::from_json(COUNTERS)
+            .unwrap()
+            .build(&registry, State::new(Context::new()))
+            .unwrap();
+        assert_eq!(flow.num_nodes(), 1);
+    }
+}
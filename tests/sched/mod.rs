@@ -1 +1,14 @@
-pub mod sched;
\ No newline at end of file
+pub mod checkpoint;
+pub mod editor;
+pub mod executor;
+pub mod flow;
+pub mod metrics;
+pub mod migration;
+pub mod recording;
+pub mod registry;
+pub mod render;
+pub mod sched;
+pub mod validation;
+pub mod version;
+#[cfg(feature = "tracing")]
+pub mod tracing;
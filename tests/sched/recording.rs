@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod recording {
+    use flowrs::{
+        connection::{connect, Edge},
+        flow::Flow,
+        node::{Context, State},
+        recording::{TrafficRecorder, TrafficReplay},
+        version::Version,
+    };
+
+    use crate::sched::flow::CounterNode;
+
+    #[test]
+    fn should_replay_recorded_traffic() {
+        let path =
+            std::env::temp_dir().join(format!("flowrs-traffic-{}.jsonl", std::process::id()));
+        let context = State::new(Context::new());
+
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let id1 = flow
+            .add_node(CounterNode::new("counter_1", context.clone()))
+            .unwrap();
+        let counter_2 = CounterNode::new("counter_2", context.clone());
+        connect(counter_2.output_1.clone(), Edge::new());
+        let id2 = flow.add_node(counter_2).unwrap();
+        flow.connect::<i32>(id1, 0, id2, 0).unwrap();
+
+        let recorder = TrafficRecorder::create(&path, context.clone()).unwrap();
+        flow.record::<i32>(id2, 0, &recorder).unwrap();
+        assert!(flow.record::<String>(id2, 0, &recorder).is_err());
+
+        let input = flow.handle(id1).unwrap().ports();
+        let input = input.lock().unwrap().input_at(0);
+        let input = input.downcast::<Edge<i32>>().unwrap();
+        flow.init_all().unwrap();
+        flow.ready_all().unwrap();
+        for epoch in 1..=2 {
+            context.0.lock().unwrap().set_epoch(epoch);
+            input.send(epoch as i32 * 10).unwrap();
+            input.send(epoch as i32 * 10 + 1).unwrap();
+            flow.handle(id1).unwrap().update().unwrap();
+        }
+        recorder.flush().unwrap();
+
+        let replay = TrafficReplay::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let records: Vec<_> = replay
+            .records()
+            .iter()
+            .map(|r| (r.epoch, r.node.as_str(), r.port, r.value.as_i64().unwrap()))
+            .collect();
+        assert_eq!(
+            records,
+            vec![
+                (1, "counter_2", 0, 10),
+                (1, "counter_2", 0, 11),
+                (2, "counter_2", 0, 20),
+                (2, "counter_2", 0, 21)
+            ]
+        );
+
+        // Replay into counter_2 in isolation.
+        let mut isolated = Flow::new("flow_2", Version::new(1, 0, 0));
+        let counter_2 = CounterNode::new("counter_2", context);
+        let output = Edge::new();
+        connect(counter_2.output_1.clone(), output.clone());
+        isolated.add_node(counter_2).unwrap();
+
+        let mut replay = replay;
+        replay.bind_flow::<i32>(&isolated, "counter_2", 0).unwrap();
+        assert_eq!(replay.feed_epoch(2).unwrap(), 2);
+        assert_eq!(replay.feed().unwrap(), 4);
+        isolated.init_all().unwrap();
+        isolated.ready_all().unwrap();
+        isolated.get_handle(0).unwrap().update().unwrap();
+
+        let mut received = Vec::new();
+        while let Ok(value) = output.next_elem() {
+            received.push(value);
+        }
+        assert_eq!(received, vec![20, 21, 10, 11, 20, 21]);
+    }
+}
@@ -0,0 +1,99 @@
+#[cfg(test)]
+mod registry {
+    use anyhow::Result;
+    use flowrs::{
+        definition::{DefinitionError, FlowDefinition},
+        node::{Context, FromProperties, State},
+        registry::NodeRegistry,
+    };
+    use schemars::JsonSchema;
+    use serde::Deserialize;
+
+    use crate::sched::flow::CounterNode;
+
+    #[derive(Deserialize, JsonSchema)]
+    #[serde(deny_unknown_fields)]
+    pub struct CounterProps {
+        start: u64,
+        #[serde(default)]
+        offset: u64,
+    }
+
+    impl FromProperties for CounterNode {
+        type Properties = CounterProps;
+
+        fn from_properties(
+            name: &str,
+            context: State<Context>,
+            props: CounterProps,
+        ) -> Result<Self> {
+            let node = CounterNode::new(name, context);
+            node.set_count(props.start + props.offset);
+            Ok(node)
+        }
+    }
+
+    fn registry() -> NodeRegistry {
+        let mut registry = NodeRegistry::new();
+        registry.register_typed_node::<CounterNode>("Counter");
+        registry
+    }
+
+    fn definition(props: &str) -> FlowDefinition {
+        FlowDefinition::from_json(&format!(
+            r#"{{
+                "name": "counters",
+                "version": "1.0.0",
+                "nodes": [
+                    {{ "name": "counter_1", "kind": "Counter", "props": {{ "start": 1 }} }},
+                    {{ "name": "counter_2", "kind": "Counter", "props": {} }}
+                ]
+            }}"#,
+            props
+        ))
+        .unwrap()
+    }
+
+    fn invalid_props(props: &str) -> (String, String) {
+        let err = match definition(props).build(&registry(), State::new(Context::new())) {
+            Ok(_) => panic!("Expected invalid props."),
+            Err(err) => err,
+        };
+        match err.downcast::<DefinitionError>().unwrap() {
+            DefinitionError::InvalidProps { node, field, .. } => (node, field),
+            err => panic!("Unexpected error {}", err),
+        }
+    }
+
+    #[test]
+    fn should_construct_from_typed_props() {
+        let flow = definition(r#"{ "start": 5, "offset": 1 }"#)
+            .build(&registry(), State::new(Context::new()))
+            .unwrap();
+        let checkpoint = flow.checkpoint().unwrap();
+        assert_eq!(checkpoint.nodes["counter_2"]["count"], 6);
+    }
+
+    #[test]
+    fn should_point_at_invalid_field() {
+        assert_eq!(
+            invalid_props(r#"{ "start": "five" }"#),
+            ("counter_2".to_string(), "/start".to_string())
+        );
+        assert_eq!(
+            invalid_props(r#"{ "start": 1, "offset": -1 }"#),
+            ("counter_2".to_string(), "/offset".to_string())
+        );
+        assert_eq!(
+            invalid_props("null"),
+            ("counter_2".to_string(), "/".to_string())
+        );
+    }
+
+    #[test]
+    fn should_expose_schema() {
+        let schema = registry().schema("Counter").unwrap().clone();
+        assert_eq!(schema["required"], serde_json::json!(["start"]));
+        assert!(NodeRegistry::new().schema("Counter").is_none());
+    }
+}
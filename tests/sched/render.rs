@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod render {
+    use flowrs::{
+        flow::Flow,
+        node::{Context, State},
+        version::Version,
+    };
+
+    use crate::sched::sched::DummyNode;
+
+    fn flow() -> Flow {
+        let context = State::new(Context::new());
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let n1 = DummyNode::new("node_1", context.clone(), false);
+        let output = n1.output_1.clone();
+        let id1 = flow.add_node(n1).unwrap();
+        let id2 = flow
+            .add_node(DummyNode::new("node_2", context, false))
+            .unwrap();
+        flow.connect::<i32>(id1, 0, id2, 0).unwrap();
+        output.clone().send(1).unwrap();
+        output.clone().send(2).unwrap();
+        flow
+    }
+
+    #[test]
+    fn should_render_dot() {
+        let expected = "digraph \"flow_1\" {
+    node [shape=box];
+    n0 [label=\"node_1\\nDummyNode\\nCreated\"];
+    n1 [label=\"node_2\\nDummyNode\\nCreated\"];
+    n0 -> n1 [label=\"output_1 -> input_1\\ni32 (2 queued)\"];
+}
+";
+        assert_eq!(flow().to_dot(), expected);
+    }
+
+    #[test]
+    fn should_render_mermaid() {
+        let expected = "flowchart LR
+    n0[\"node_1<br/>DummyNode<br/>Created\"]
+    n1[\"node_2<br/>DummyNode<br/>Created\"]
+    n0 -->|\"output_1 -#gt; input_1<br/>i32 (2 queued)\"| n1
+";
+        assert_eq!(flow().to_mermaid(), expected);
+    }
+}
@@ -1,12 +1,16 @@
 use flowrs::{node::{ State, Context, Node, InitError, ReadyError, ShutdownError, UpdateError}};
-use flowrs::connection::{Input, Output};
+use flowrs::connection::{Input, Output, RuntimeConnectable};
+use flowrs_derive::Connectable;
 
-use std::fs::File;
+use std::{any::Any, fs::File, rc::Rc};
 
+#[derive(Connectable)]
 pub struct DummyNode {
     name: String,
 
+    #[input]
     pub input_1: Input<i32>,
+    #[output]
     pub output_1: Output<i32>,
     err_on_init: bool
 }
@@ -17,7 +21,7 @@ impl DummyNode {
             name: name.into(),
             input_1: Input::new(),
             output_1: Output::new(context.clone()),
-            err_on_init: err_on_init
+            err_on_init
         }
     }
 }
@@ -51,9 +55,8 @@ impl Node for DummyNode {
 #[cfg(test)]
 mod sched {
     
-    use flowrs::{executor::{Executor, MultiThreadedExecutor}, scheduler::{RoundRobinScheduler}, node::{Context, State, Node, InitError, ReadyError, ShutdownError, UpdateError}, flow::Flow, version::Version};
-    use flowrs::connection::{connect, Edge, Input};
-    use serde_json::Value;
+    use flowrs::{executor::{Executor, MultiThreadedExecutor}, scheduler::{RoundRobinScheduler}, node::{Context, State}, flow::Flow, version::Version};
+    use flowrs::connection::{connect, Input};
 
     use std::{thread, sync::mpsc, time::Duration};
     use crate::sched::sched::DummyNode;
@@ -72,20 +75,20 @@ mod sched {
 
         let mut flow = Flow::new("flow_1", Version::new(1,0,0));
 
-        n1.input_1.send(1);
+        let _ = n1.input_1.send(1);
       
         
-        flow.add_node(n1);
+        flow.add_node(n1).unwrap();
 
         let thread_handle = thread::spawn( move || {
         
             let num_threads = 4;
             let mut executor = MultiThreadedExecutor::new(num_threads, context);
-            let mut scheduler = RoundRobinScheduler::new();
+            let scheduler = RoundRobinScheduler::new();
 
             let _ = sender.send(executor.controller());
 
-            executor.run(flow, scheduler);
+            let _ = executor.run(flow, scheduler);
         });
 
         let controller = receiver.recv().unwrap();
@@ -110,10 +113,12 @@ mod sched {
 
        let n1: DummyNode = DummyNode::new("node_1", context.clone(), true);
        let n2: DummyNode = DummyNode::new("node_2", context.clone(), true);
+       connect(n2.output_1.clone(), Input::new());
        let mut flow = Flow::new("flow_1", Version::new(1,0,0));
       
-       flow.add_node(n1);
-       flow.add_node(n2);
+       let id1 = flow.add_node(n1).unwrap();
+       let id2 = flow.add_node(n2).unwrap();
+       flow.connect::<i32>(id1, 0, id2, 0).unwrap();
 
        let mut ex = MultiThreadedExecutor::new(1, context);
 
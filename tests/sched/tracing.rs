@@ -0,0 +1,134 @@
+use flowrs::connection::{Input, RuntimeConnectable};
+use flowrs::node::{Node, UpdateError};
+use flowrs_derive::Connectable;
+
+use std::{any::Any, rc::Rc};
+
+/// Fails every update.
+#[derive(Connectable)]
+pub struct FailingNode {
+    name: String,
+
+    #[input]
+    pub input_1: Input<i32>,
+}
+
+impl FailingNode {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            input_1: Input::new(),
+        }
+    }
+}
+
+impl Node for FailingNode {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn update(&self) -> Result<(), UpdateError> {
+        Err(UpdateError::Other(anyhow::anyhow!("Failed.")))
+    }
+}
+
+#[cfg(test)]
+mod tracing {
+    use flowrs::{
+        executor::{Executor, MultiThreadedExecutor},
+        flow::Flow,
+        node::{Context, State},
+        scheduler::RoundRobinScheduler,
+        version::Version,
+    };
+    use std::{
+        collections::BTreeMap,
+        fmt,
+        sync::{mpsc, Arc, Mutex},
+        thread,
+        time::Duration,
+    };
+    use tracing::{
+        field::{Field, Visit},
+        span, Event, Metadata, Subscriber,
+    };
+
+    use super::FailingNode;
+
+    /// The fields of an event by name.
+    #[derive(Default)]
+    struct Fields(BTreeMap<String, String>);
+
+    /// Records the fields of all events.
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<Fields>>>);
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            self.0.insert(field.name().into(), format!("{:?}", value));
+        }
+
+        fn record_u64(&mut self, field: &Field, value: u64) {
+            self.0.insert(field.name().into(), value.to_string());
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name().into(), value.into());
+        }
+    }
+
+    impl Subscriber for Capture {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _span: &span::Attributes<'_>) -> span::Id {
+            span::Id::from_u64(1)
+        }
+
+        fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Fields::default();
+            event.record(&mut fields);
+            self.0.lock().unwrap().push(fields);
+        }
+
+        fn enter(&self, _span: &span::Id) {}
+
+        fn exit(&self, _span: &span::Id) {}
+    }
+
+    #[test]
+    fn should_trace_failed_updates_with_epoch() {
+        let capture = Capture::default();
+        // Updates run on the threads of the executor, so the subscriber has to be global.
+        tracing::subscriber::set_global_default(capture.clone()).unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        let context = State::new(Context::new());
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        flow.add_node(FailingNode::new("failing")).unwrap();
+
+        let thread_handle = thread::spawn(move || {
+            let mut executor = MultiThreadedExecutor::new(2, context);
+            let _ = sender.send(executor.controller());
+            executor.run(flow, RoundRobinScheduler::new())
+        });
+        let controller = receiver.recv().unwrap();
+        thread::sleep(Duration::from_millis(20));
+        controller.lock().unwrap().cancel();
+        thread_handle.join().unwrap().unwrap();
+
+        let events = capture.0.lock().unwrap();
+        let failed = events
+            .iter()
+            .find(|fields| fields.0["message"] == "Node update failed.")
+            .unwrap();
+        assert_eq!(failed.0["node"], "failing");
+        assert_eq!(failed.0["error"], "Unable to update node 'failing'.");
+        assert!(failed.0["epoch"].parse::<u64>().unwrap() >= 1);
+    }
+}
@@ -0,0 +1,87 @@
+#[cfg(test)]
+mod validation {
+    use flowrs::{
+        connection::{connect, Input},
+        executor::{Executor, MultiThreadedExecutor},
+        flow::{Flow, FlowError},
+        node::{Context, State},
+        scheduler::RoundRobinScheduler,
+        validation::{Severity, ValidationIssue},
+        version::Version,
+    };
+
+    use crate::sched::sched::DummyNode;
+
+    #[test]
+    fn should_accept_connected_flow() {
+        let context = State::new(Context::new());
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let n2 = DummyNode::new("node_2", context.clone(), false);
+        connect(n2.output_1.clone(), Input::new());
+        let id1 = flow
+            .add_node(DummyNode::new("node_1", context, false))
+            .unwrap();
+        let id2 = flow.add_node(n2).unwrap();
+        flow.connect::<i32>(id1, 0, id2, 0).unwrap();
+
+        let report = flow.validate();
+        assert!(!report.has_errors(), "{}", report);
+        assert_eq!(
+            report.issues(),
+            &[ValidationIssue::UnconnectedInput {
+                node: "node_1".into(),
+                index: 0
+            }]
+        );
+        assert_eq!(report.issues()[0].severity(), Severity::Warning);
+    }
+
+    #[test]
+    fn should_report_unconnected_outputs_and_unreachable_nodes() {
+        let context = State::new(Context::new());
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let id1 = flow
+            .add_node(DummyNode::new("node_1", context.clone(), false))
+            .unwrap();
+        let id2 = flow
+            .add_node(DummyNode::new("node_2", context.clone(), false))
+            .unwrap();
+        flow.add_node(DummyNode::new("node_3", context, false))
+            .unwrap();
+        flow.connect::<i32>(id1, 0, id2, 0).unwrap();
+        flow.connect::<i32>(id2, 0, id1, 0).unwrap();
+
+        let report = flow.validate();
+        assert!(report.has_errors());
+        assert_eq!(
+            report.errors().collect::<Vec<_>>(),
+            vec![&ValidationIssue::UnconnectedOutput {
+                node: "node_3".into(),
+                index: 0
+            }]
+        );
+        assert!(report.warnings().any(|i| i
+            == &ValidationIssue::Unreachable {
+                node: "node_1".into()
+            }));
+        assert!(report.warnings().any(|i| i
+            == &ValidationIssue::Unreachable {
+                node: "node_2".into()
+            }));
+    }
+
+    #[test]
+    fn should_refuse_to_run_invalid_flow() {
+        let context = State::new(Context::new());
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        flow.add_node(DummyNode::new("node_1", context.clone(), false))
+            .unwrap();
+
+        let mut ex = MultiThreadedExecutor::new(1, context);
+        let err = ex.run(flow, RoundRobinScheduler::new()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<FlowError>(),
+            Some(FlowError::Invalid(_))
+        ));
+    }
+}
@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod version {
+    use flowrs::{
+        definition::{DefinitionError, FlowDefinition},
+        node::{Context, State},
+        registry::NodeRegistry,
+        version::Version,
+    };
+
+    fn v(s: &str) -> Version {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn should_parse_and_format() {
+        let version = v("1.2.3");
+        assert_eq!(version, Version::new(1, 2, 3));
+        assert_eq!(version.to_string(), "1.2.3");
+
+        let version = v("1.2.3-beta.1");
+        assert_eq!(version.pre(), Some("beta.1"));
+        assert_eq!(version, Version::new(1, 2, 3).with_pre("beta.1").unwrap());
+        assert_eq!(version.to_string(), "1.2.3-beta.1");
+
+        for invalid in ["1.2", "1.2.3.4", "1.02.3", "1.2.x", "1.2.3-", "1.2.3-a..b", "-1.2.3"] {
+            assert!(invalid.parse::<Version>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn should_order_by_precedence() {
+        let ordered = [
+            "0.9.0",
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.1",
+            "1.10.0",
+        ];
+        for pair in ordered.windows(2) {
+            assert!(v(pair[0]) < v(pair[1]), "{} < {}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn should_check_compatibility() {
+        assert!(v("1.2.3").is_compatible_with(&v("1.2.3")));
+        assert!(v("1.4.0").is_compatible_with(&v("1.2.3")));
+        assert!(!v("1.2.2").is_compatible_with(&v("1.2.3")));
+        assert!(!v("2.0.0").is_compatible_with(&v("1.2.3")));
+        assert!(!v("1.2.3-rc.1").is_compatible_with(&v("1.2.3")));
+        assert!(v("0.2.5").is_compatible_with(&v("0.2.1")));
+        assert!(!v("0.3.0").is_compatible_with(&v("0.2.1")));
+    }
+
+    #[test]
+    fn should_serialize_as_string() {
+        let version = v("1.2.3-rc.1");
+        let json = serde_json::to_string(&version).unwrap();
+        assert_eq!(json, "\"1.2.3-rc.1\"");
+        assert_eq!(serde_json::from_str::<Version>(&json).unwrap(), version);
+        assert!(serde_json::from_str::<Version>("\"1.x\"").is_err());
+    }
+
+    #[test]
+    fn should_reject_definition_for_incompatible_runtime() {
+        let runtime = Version::runtime();
+        let json = format!(
+            r#"{{ "name": "flow_1", "version": "1.0.0", "runtime": "{}.0.0" }}"#,
+            runtime.major() + 1
+        );
+        let definition = FlowDefinition::from_json(&json).unwrap();
+        let err = match definition.build(&NodeRegistry::new(), State::new(Context::new())) {
+            Ok(_) => panic!("Expected an incompatible runtime."),
+            Err(err) => err,
+        };
+        assert!(matches!(
+            err.downcast_ref::<DefinitionError>(),
+            Some(DefinitionError::IncompatibleRuntime { .. })
+        ));
+
+        let json = format!(
+            r#"{{ "name": "flow_1", "version": "1.0.0", "runtime": "{}" }}"#,
+            runtime
+        );
+        let definition = FlowDefinition::from_json(&json).unwrap();
+        assert!(definition
+            .build(&NodeRegistry::new(), State::new(Context::new()))
+            .is_ok());
+    }
+}
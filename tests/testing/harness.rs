@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod harness {
+    use flowrs::{
+        flow::Flow,
+        node::{Context, State, UpdateError},
+        testing::{FlowHarness, HarnessError, Probe},
+        version::Version,
+    };
+    use serde_json::Value;
+
+    use crate::{nodes::node::AddNode, sched::flow::CounterNode};
+
+    fn flow(context: &State<Context>) -> Flow {
+        let mut flow = Flow::new("flow_1", Version::new(1, 0, 0));
+        let counter = flow
+            .add_node(CounterNode::new("counter", context.clone()))
+            .unwrap();
+        let add = flow
+            .add_node(AddNode::<i32, i32, i32>::new(
+                "add",
+                context.clone(),
+                Value::Null,
+            ))
+            .unwrap();
+        flow.connect::<i32>(counter, 0, add, 0).unwrap();
+        flow
+    }
+
+    #[test]
+    fn should_run_flow_until_idle() {
+        let context = State::new(Context::new());
+        let mut harness = FlowHarness::new(flow(&context), context);
+        let counter = harness.feeder::<i32>("counter", 0).unwrap();
+        let add = harness.feeder::<i32>("add", 1).unwrap();
+        let sums: Probe<i32> = harness.probe("add", 0).unwrap();
+        assert!(harness.probe::<i32>("add", 0).is_err());
+        assert!(harness.feeder::<String>("add", 1).is_err());
+
+        counter.send_all([1, 2, 3]);
+        add.send_all([10, 20, 30]);
+        // The add node takes one element per input and update.
+        assert_eq!(harness.run_until_idle().unwrap(), 3);
+        assert!(harness.is_idle());
+        assert_eq!(sums.values(), vec![11, 22, 33]);
+        assert!(harness.errors().is_empty());
+
+        add.send(40);
+        harness.run_until_idle().unwrap();
+        assert_eq!(sums.take(), vec![11, 22, 33]);
+        assert!(sums.is_empty());
+        assert_eq!(harness.epoch(), 4);
+        harness.shutdown().unwrap();
+    }
+
+    #[test]
+    fn should_record_failed_updates() {
+        let context = State::new(Context::new());
+        let mut harness = FlowHarness::new(flow(&context), context);
+        let add = harness.feeder::<i32>("add", 1).unwrap();
+        let _sums = harness.probe::<i32>("add", 0).unwrap();
+
+        add.send_all([1, 2]);
+        harness.run_until_idle().unwrap();
+        let errors = harness.take_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!((errors[0].epoch, errors[0].node.as_str()), (2, "add"));
+        assert!(matches!(
+            errors[0].error.downcast_ref::<UpdateError>(),
+            Some(UpdateError::SequenceError { .. })
+        ));
+        assert!(harness.check_errors().is_ok());
+    }
+
+    #[test]
+    fn should_give_up_on_busy_flow() {
+        let context = State::new(Context::new());
+        let mut harness = FlowHarness::new(flow(&context), context);
+        let add = harness.feeder::<i32>("add", 1).unwrap();
+        let _sums = harness.probe::<i32>("add", 0).unwrap();
+
+        add.send_all([1, 2, 3]);
+        harness.set_max_epochs(1);
+        let err = harness.run_until_idle().unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<HarnessError>(),
+            Some(HarnessError::NotIdle { epochs: 1 })
+        ));
+    }
+}
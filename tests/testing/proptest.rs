@@ -0,0 +1,154 @@
+#[cfg(all(test, feature = "proptest"))]
+mod proptest {
+    use ::proptest::{
+        collection::vec,
+        prop_assert_eq, prop_oneof,
+        sample::select,
+        strategy::Strategy,
+        test_runner::{Config, TestError},
+    };
+    use flowrs::testing::proptest::{check_node, send_to, stimuli, AnyStimulus, Run, Stimulus};
+    use std::{any::Any, rc::Rc};
+
+    use crate::nodes::node::AddNode;
+
+    fn config() -> Config {
+        Config {
+            cases: 64,
+            failure_persistence: None,
+            ..Config::default()
+        }
+    }
+
+    /// The outputs and failed stimuli of a pairwise add node, with every element sent on its own.
+    fn model(stimuli: &[Stimulus<i32>]) -> (Vec<i32>, Vec<usize>) {
+        let mut pending: Option<Stimulus<i32>> = None;
+        let (mut sums, mut errors) = (Vec::new(), Vec::new());
+        for (index, stimulus) in stimuli.iter().enumerate() {
+            match pending.take() {
+                None => pending = Some(stimulus.clone()),
+                Some(first) if first.port == stimulus.port => {
+                    errors.push(index);
+                    pending = Some(first);
+                }
+                Some(first) => sums.push(first.value + stimulus.value),
+            }
+        }
+        (sums, errors)
+    }
+
+    #[test]
+    fn should_match_model_of_add_node() {
+        check_node(
+            config(),
+            stimuli(2, -100..100i32, 0..20),
+            |context| AddNode::<i32, i32, i32>::new("add", context, serde_json::Value::Null),
+            |stimuli, run: &Run<i32>| {
+                let (sums, errors) = model(stimuli);
+                prop_assert_eq!(&run.outputs, &vec![sums]);
+                let failed: Vec<_> = run.errors.iter().map(|(index, _)| *index).collect();
+                prop_assert_eq!(failed, errors);
+                Ok(())
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn should_shrink_failing_sequence() {
+        let res = check_node(
+            config(),
+            stimuli(2, -100..100i32, 0..20),
+            |context| AddNode::<i32, i32, i32>::new("add", context, serde_json::Value::Null),
+            |_, run: &Run<i32>| {
+                prop_assert_eq!(run.errors.len(), 0);
+                Ok(())
+            },
+        );
+        match res {
+            Err(TestError::Fail(_, minimal)) => {
+                assert_eq!(minimal.len(), 2);
+                assert_eq!(minimal[0].port, minimal[1].port);
+                assert_eq!((minimal[0].value, minimal[1].value), (0, 0));
+            }
+            _ => panic!("Expected a failing sequence."),
+        }
+    }
+
+    /// A stimulus for an add node concatenating owned and borrowed strings.
+    #[derive(Debug, Clone)]
+    enum Part {
+        Owned(String),
+        Borrowed(&'static str),
+    }
+
+    impl AnyStimulus for Part {
+        fn port(&self) -> usize {
+            match self {
+                Part::Owned(_) => 0,
+                Part::Borrowed(_) => 1,
+            }
+        }
+
+        fn send_to(&self, input: Rc<dyn Any>) -> bool {
+            match self {
+                Part::Owned(value) => send_to(input, value.clone()),
+                Part::Borrowed(value) => send_to(input, *value),
+            }
+        }
+    }
+
+    #[test]
+    fn should_send_stimuli_of_different_types() {
+        let parts = prop_oneof![
+            "[a-z]{0,3}".prop_map(Part::Owned),
+            select(vec!["x", "y"]).prop_map(Part::Borrowed),
+        ];
+        check_node(
+            config(),
+            vec(parts, 0..20),
+            |context| {
+                AddNode::<String, &'static str, String>::new(
+                    "concat",
+                    context,
+                    serde_json::Value::Null,
+                )
+            },
+            |stimuli, run: &Run<String>| {
+                let (mut owned, mut borrowed, mut joined) = (None, None, Vec::new());
+                for stimulus in stimuli {
+                    match stimulus {
+                        Part::Owned(value) if owned.is_none() => owned = Some(value.clone()),
+                        Part::Borrowed(value) if borrowed.is_none() => borrowed = Some(*value),
+                        _ => {}
+                    }
+                    if let (Some(lhs), Some(rhs)) = (&owned, borrowed) {
+                        joined.push(lhs.clone() + rhs);
+                        (owned, borrowed) = (None, None);
+                    }
+                }
+                prop_assert_eq!(&run.outputs, &vec![joined]);
+                Ok(())
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn should_fail_on_unexpected_output_type() {
+        let res = check_node(
+            config(),
+            stimuli(2, -100..100i32, 0..20),
+            |context| AddNode::<i32, i32, i32>::new("add", context, serde_json::Value::Null),
+            |_, _: &Run<String>| Ok(()),
+        );
+        match res {
+            Err(TestError::Fail(reason, _)) => {
+                assert!(reason
+                    .to_string()
+                    .contains("does not send the expected element type"))
+            }
+            _ => panic!("Expected the output type to be rejected."),
+        }
+    }
+}